@@ -0,0 +1,14 @@
+#!/bin/sh
+# Download the IP-to-ASN/country table for `geoip.path` from https://iptoasn.com.
+# Usage: scripts/update-geoip.sh [output path], then restart dashbook to load it.
+set -eu
+
+out="${1:-/var/lib/dashbook/ip2asn-combined.tsv.gz}"
+url="${IP2ASN_URL:-https://iptoasn.com/data/ip2asn-combined.tsv.gz}"
+tmp="$(mktemp "$out.XXXXXX")"
+trap 'rm -f "$tmp"' EXIT
+
+curl -fsSL "$url" -o "$tmp"
+gzip -t "$tmp"
+mv "$tmp" "$out"
+echo "Wrote $(gzip -dc "$out" | wc -l) ranges to $out"
@@ -2,7 +2,7 @@ use axum::extract::{Path, Query, State};
 use axum::Json;
 use serde::Deserialize;
 
-use crate::models::address::{AddressInfo, AddressTxEntry, AddressUtxo};
+use crate::models::address::{AddressInfo, AddressTxEntry, AddressUtxo, RichListResponse};
 use crate::AppError;
 use crate::AppState;
 
@@ -18,12 +18,12 @@ pub async fn get_address(
     Query(params): Query<AddressParams>,
 ) -> Result<Json<AddressInfo>, AppError> {
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(50).min(200);
+    let limit = params.limit.unwrap_or(50).min(state.config.limits.address_page);
 
     // Fetch balance and txids in parallel (skip utxos initially)
     let (balance_res, txids_res) = tokio::join!(
-        state.rpc.get_address_balance(&address),
-        state.rpc.get_address_txids(&address),
+        state.get_address_balance(&address),
+        state.get_address_txids(&address),
     );
 
     let balance = balance_res?;
@@ -41,7 +41,7 @@ pub async fn get_address(
 
     // Only fetch deltas if we have a reasonable tx count, otherwise just show txids without deltas
     let (tx_delta_map, tx_height_map) = if tx_count <= 10_000 {
-        let deltas = state.rpc.get_address_deltas(&address, None, None).await?;
+        let deltas = state.get_address_deltas(&address, None, None).await?;
         let mut delta_map: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
         let mut height_map: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
         for d in &deltas {
@@ -55,10 +55,12 @@ pub async fn get_address(
         // For very large addresses, fetch deltas individually per page txid via getrawtransaction
         let mut delta_map: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
         let mut height_map: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
-        for txid in &page_txids {
-            if let Ok(tx) = state.rpc.get_raw_transaction(txid).await {
+        let txids: Vec<String> = page_txids.iter().map(|t| (*t).clone()).collect();
+        let txs = state.rpc.get_raw_transactions(&txids).await?;
+        for (txid, tx) in txids.iter().zip(txs) {
+            if let Ok(tx) = tx {
                 let height = tx.height.unwrap_or(0);
-                height_map.insert((*txid).clone(), height);
+                height_map.insert(txid.clone(), height);
                 // Compute delta: sum outputs to this address minus inputs from this address
                 let mut delta: i64 = 0;
                 for output in &tx.vout {
@@ -75,7 +77,7 @@ pub async fn get_address(
                         }
                     }
                 }
-                delta_map.insert((*txid).clone(), delta);
+                delta_map.insert(txid.clone(), delta);
             }
         }
         (delta_map, height_map)
@@ -97,7 +99,7 @@ pub async fn get_address(
 
     // Only fetch UTXOs for addresses with a reasonable count
     let utxos: Vec<AddressUtxo> = if tx_count <= 50_000 {
-        let raw_utxos = state.rpc.get_address_utxos(&address).await?;
+        let raw_utxos = state.get_address_utxos(&address).await?;
         raw_utxos
             .iter()
             .map(|u| AddressUtxo {
@@ -127,3 +129,37 @@ pub async fn get_address(
 
     Ok(Json(info))
 }
+
+pub async fn get_richlist(
+    State(state): State<AppState>,
+    Query(params): Query<AddressParams>,
+) -> Result<Json<RichListResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(50).min(state.config.limits.richlist_page).max(1);
+
+    let index = state.index.as_ref().ok_or_else(|| {
+        AppError::NotFound("The rich list requires the local address index".into())
+    })?;
+    let list = index
+        .richlist()
+        .ok_or_else(|| AppError::Unavailable("The rich list is still being built".into()))?;
+
+    let addresses = list
+        .top
+        .iter()
+        .skip((page as usize - 1).saturating_mul(limit as usize))
+        .take(limit as usize)
+        .cloned()
+        .collect();
+
+    Ok(Json(RichListResponse {
+        height: list.height,
+        updated_at: list.updated_at,
+        total_addresses: list.total_addresses,
+        total_balance: list.total_sat as f64 / 100_000_000.0,
+        page,
+        pages: (list.top.len() as u32).div_ceil(limit),
+        addresses,
+        distribution: list.distribution.clone(),
+    }))
+}
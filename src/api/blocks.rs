@@ -1,9 +1,14 @@
 use axum::extract::{Path, Query, State};
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-use crate::models::block::{BlockDetail, BlockSummary, CbTxInfo};
+use crate::models::block::{
+    BlockDetail, BlockReward, BlockStats, BlockSummary, CbTxInfo, MasternodePayee, TxTypeCount,
+};
 use crate::models::transaction::TransactionSummary;
+use crate::pools;
+use crate::rpc::types::{RpcMasternodePayments, RpcTransaction};
 use crate::AppError;
 use crate::AppState;
 
@@ -26,7 +31,7 @@ pub async fn list_blocks(
     Query(params): Query<BlockListParams>,
 ) -> Result<Json<BlockListResponse>, AppError> {
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(20).min(100);
+    let limit = params.limit.unwrap_or(20).min(state.config.limits.blocks_page);
 
     let tip = state.rpc.get_block_count().await?;
     let total_pages = (tip / limit as u64) + 1;
@@ -34,13 +39,25 @@ pub async fn list_blocks(
     let start = tip.saturating_sub((page as u64 - 1) * limit as u64);
     let end = start.saturating_sub(limit as u64 - 1);
 
-    let count = (start - end + 1) as usize;
-    let mut blocks = Vec::with_capacity(count);
-    for h in (end..=start).rev() {
-        let hash = state.rpc.get_block_hash(h).await?;
-        let block = state.rpc.get_block_header(&hash).await?;
-        blocks.push(BlockSummary::from_rpc(&block));
-    }
+    let heights: Vec<u64> = (end..=start).rev().collect();
+    let hashes = state.rpc.get_block_hashes(&heights).await?;
+    let (headers, stats, miners) = tokio::join!(
+        state.rpc.get_block_headers(&hashes),
+        block_stats(&state, &heights, &hashes),
+        pools::block_miners(&state, &hashes),
+    );
+    let (headers, stats) = (headers?, stats?);
+    let mut miners = miners.ok().map(Vec::into_iter);
+
+    let blocks = headers
+        .iter()
+        .zip(stats)
+        .map(|(header, stats)| BlockSummary {
+            stats,
+            miner: miners.as_mut().and_then(Iterator::next),
+            ..BlockSummary::from_rpc(header)
+        })
+        .collect();
 
     Ok(Json(BlockListResponse {
         blocks,
@@ -50,10 +67,73 @@ pub async fn list_blocks(
     }))
 }
 
+/// `getblockstats` totals for blocks at `heights` (hashes alongside), batching only
+/// those not cached yet. Blocks the node can't produce stats for come back as None.
+async fn block_stats(
+    state: &AppState,
+    heights: &[u64],
+    hashes: &[String],
+) -> Result<Vec<Option<BlockStats>>, AppError> {
+    let mut stats: Vec<Option<BlockStats>> = Vec::with_capacity(hashes.len());
+    let mut missing: Vec<usize> = Vec::new();
+    for (i, hash) in hashes.iter().enumerate() {
+        let cached = state.cache.block_stats.get(hash).await;
+        if cached.is_none() {
+            missing.push(i);
+        }
+        stats.push(cached);
+    }
+
+    let missing_heights: Vec<u64> = missing.iter().map(|&i| heights[i]).collect();
+    let fetched = state.rpc.get_block_stats_batch(&missing_heights).await?;
+    for (i, result) in missing.into_iter().zip(fetched) {
+        let Ok(rpc_stats) = result else {
+            continue;
+        };
+        // Stats are keyed by hash, so a reorg between the two lookups can't mix blocks up
+        if rpc_stats.blockhash != hashes[i] {
+            continue;
+        }
+        let block = BlockStats::from_rpc(&rpc_stats);
+        state.cache.block_stats.insert(hashes[i].clone(), block.clone()).await;
+        stats[i] = Some(block);
+    }
+    Ok(stats)
+}
+
+#[derive(Deserialize)]
+pub struct BlockTxParams {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+    /// Comma-separated special transaction types, by number or label (e.g. `1,AssetLock`)
+    #[serde(rename = "type")]
+    pub tx_type: Option<String>,
+}
+
 pub async fn get_block(
     State(state): State<AppState>,
     Path(hash_or_height): Path<String>,
+    Query(params): Query<BlockTxParams>,
 ) -> Result<Json<BlockDetail>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params
+        .limit
+        .unwrap_or(50)
+        .min(state.config.limits.block_txs_page)
+        .max(1);
+    let types: Option<Vec<String>> = params.tx_type.as_ref().map(|t| {
+        t.split(',')
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect()
+    });
+    let filter = |tx: &TransactionSummary| match types {
+        Some(ref types) => types
+            .iter()
+            .any(|t| *t == tx.tx_type.to_string() || *t == tx.tx_type_label.to_lowercase()),
+        None => true,
+    };
+
     // Determine if input is height (numeric) or hash
     let hash = if hash_or_height.chars().all(|c| c.is_ascii_digit()) {
         let height: u64 = hash_or_height
@@ -66,16 +146,29 @@ pub async fn get_block(
 
     // Check cache first
     if let Some(cached) = state.cache.blocks.get(&hash).await {
-        return Ok(Json(cached));
+        return Ok(Json(cached.page(filter, page, limit)));
     }
 
     // Fetch block with full transaction details
     let block = state.rpc.get_block(&hash, 2).await?;
+    let txs = block.transactions().unwrap_or_default();
+
+    // The block is still shown when stats or the masternode payees are unavailable
+    let (heights, hashes) = ([block.height], [block.hash.clone()]);
+    let (stats, payments) = tokio::join!(
+        block_stats(&state, &heights, &hashes),
+        state.rpc.get_masternode_payments(&block.hash),
+    );
+    let stats = stats.ok().and_then(|s| s.into_iter().next().flatten());
+    let payments = payments
+        .ok()
+        .and_then(|p| p.into_iter().find(|p| p.blockhash == block.hash));
+    let reward = txs
+        .first()
+        .map(|coinbase| block_reward(coinbase, payments.as_ref()));
 
-    let transactions: Vec<TransactionSummary> = block
-        .transactions()
-        .map(|txs| txs.iter().map(TransactionSummary::from_rpc).collect())
-        .unwrap_or_default();
+    let transactions: Vec<TransactionSummary> =
+        txs.iter().map(TransactionSummary::from_rpc).collect();
 
     let detail = BlockDetail {
         hash: block.hash.clone(),
@@ -95,12 +188,24 @@ pub async fn get_block(
         next_block_hash: block.next_block_hash.clone(),
         chainlock: block.chainlock,
         cb_tx: block.cb_tx.as_ref().map(CbTxInfo::from_rpc),
+        stats,
+        reward,
+        miner: txs.first().map(|coinbase| state.pools.identify(coinbase)),
+        tx_types: TxTypeCount::count(
+            transactions
+                .iter()
+                .map(|tx| (tx.tx_type, tx.tx_type_label.as_str())),
+        ),
+        tx_total: transactions.len(),
+        page: 1,
+        pages: 1,
         transactions,
     };
+    let response = detail.page(filter, page, limit);
 
     // Cache if deeply confirmed
     if block.confirmations > 6 {
-        state.cache.blocks.insert(hash.clone(), detail.clone()).await;
+        state.cache.blocks.insert(hash.clone(), Arc::new(detail)).await;
         state
             .cache
             .block_hash_by_height
@@ -108,5 +213,39 @@ pub async fn get_block(
             .await;
     }
 
-    Ok(Json(detail))
+    Ok(Json(response))
+}
+
+/// Split the coinbase into the miner's output, the masternode and operator payments the
+/// node recorded for the block, and the rest.
+fn block_reward(
+    coinbase: &RpcTransaction,
+    payments: Option<&RpcMasternodePayments>,
+) -> BlockReward {
+    let total: i64 = coinbase.vout.iter().map(|o| o.value_sat).sum();
+    let miner = coinbase.vout.first().map(|o| o.value_sat).unwrap_or(0);
+
+    // The owner's output comes first; any after it are the operator's share
+    let paid = payments
+        .and_then(|p| p.masternodes.first())
+        .and_then(|mn| Some((mn, mn.payees.split_first()?)));
+    let (masternode, operator): (i64, i64) = match paid {
+        Some((_, (owner, operators))) => (owner.amount, operators.iter().map(|o| o.amount).sum()),
+        None => (0, 0),
+    };
+
+    BlockReward {
+        total: total as f64 / 1e8,
+        miner: miner as f64 / 1e8,
+        masternode: masternode as f64 / 1e8,
+        operator: operator as f64 / 1e8,
+        other: (total - miner - masternode - operator) as f64 / 1e8,
+        masternode_payee: paid.map(|(mn, (owner, operators))| MasternodePayee {
+            pro_tx_hash: mn.pro_tx_hash.clone(),
+            address: owner.address.clone(),
+            amount: masternode as f64 / 1e8,
+            operator_address: operators.first().map(|o| o.address.clone()),
+            operator_amount: operator as f64 / 1e8,
+        }),
+    }
 }
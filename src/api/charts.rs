@@ -0,0 +1,82 @@
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::charts::{Interval, Metric};
+use crate::models::chart::ChartResponse;
+use crate::AppError;
+use crate::AppState;
+
+/// Range shown when `from` is omitted
+const DEFAULT_RANGE_SECS: u64 = 30 * 86_400;
+
+#[derive(Deserialize)]
+pub struct ChartParams {
+    /// Unix timestamps, inclusive
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub interval: Option<String>,
+}
+
+pub async fn get_chart(
+    State(state): State<AppState>,
+    Path(metric): Path<String>,
+    Query(params): Query<ChartParams>,
+) -> Result<Json<ChartResponse>, AppError> {
+    let metric = Metric::parse(&metric).ok_or_else(|| {
+        let names: Vec<&str> = Metric::ALL.iter().map(|m| m.name()).collect();
+        AppError::BadRequest(format!(
+            "Unknown metric {:?}; expected one of {}",
+            metric,
+            names.join(", ")
+        ))
+    })?;
+    let interval_name = params.interval.unwrap_or_else(|| "day".to_string());
+    let interval = Interval::parse(&interval_name).ok_or_else(|| {
+        AppError::BadRequest("interval must be one of block, hour, day, week, month".into())
+    })?;
+
+    let charts = state.charts.as_ref().ok_or_else(|| {
+        AppError::NotFound("Charts require the chart store ([charts] path)".into())
+    })?;
+
+    let cache_key = format!(
+        "{}:{}:{:?}:{:?}",
+        metric.name(),
+        interval_name,
+        params.from,
+        params.to
+    );
+    if let Some(cached) = state.cache.charts.get(&cache_key).await {
+        return Ok(Json((*cached).clone()));
+    }
+
+    let to = params
+        .to
+        .unwrap_or_else(|| chrono::Utc::now().timestamp() as u64);
+    let from = params
+        .from
+        .unwrap_or_else(|| to.saturating_sub(DEFAULT_RANGE_SECS));
+    if from > to {
+        return Err(AppError::BadRequest("from must not be after to".into()));
+    }
+
+    let max_points = state.config.limits.chart_points as usize;
+    let (points, synced_height) = tokio::try_join!(
+        charts.series(metric, interval, from, to, max_points),
+        charts.height(),
+    )?;
+
+    let response = Arc::new(ChartResponse {
+        metric: metric.name().to_string(),
+        unit: metric.unit().to_string(),
+        interval: interval_name,
+        from,
+        to,
+        synced_height,
+        points,
+    });
+    state.cache.charts.insert(cache_key, response.clone()).await;
+    Ok(Json((*response).clone()))
+}
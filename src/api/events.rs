@@ -1,11 +1,161 @@
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::extract::State;
 use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tokio::sync::broadcast;
 
 use crate::live::LiveEvent;
 use crate::AppState;
 
+/// Upper bound on addresses + txids + proTxHashes a single socket may watch
+const MAX_WATCHED_ITEMS: usize = 1000;
+/// Txids remembered per socket so InstantLocks follow matched transactions
+const MAX_FOLLOWED_TXIDS: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+    Blocks,
+    Mempool,
+    Chainlocks,
+    Governance,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SubscriptionRequest {
+    #[serde(default)]
+    pub topics: Vec<Topic>,
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub txids: Vec<String>,
+    #[serde(default)]
+    pub pro_tx_hashes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum ClientMessage {
+    Subscribe(SubscriptionRequest),
+    Unsubscribe(SubscriptionRequest),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "data")]
+enum ServerMessage {
+    Subscribed {
+        topics: Vec<Topic>,
+        addresses: usize,
+        txids: usize,
+        pro_tx_hashes: usize,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// What a socket wants to receive. Sockets that never subscribe get every event.
+#[derive(Debug, Default)]
+pub struct Subscription {
+    filtered: bool,
+    topics: HashSet<Topic>,
+    addresses: HashSet<String>,
+    txids: HashSet<String>,
+    pro_tx_hashes: HashSet<String>,
+    /// Transactions matched by address or proTxHash, so their InstantLock is delivered too
+    followed: HashSet<String>,
+}
+
+impl Subscription {
+    fn watched(&self) -> usize {
+        self.addresses.len() + self.txids.len() + self.pro_tx_hashes.len()
+    }
+
+    pub fn subscribe(&mut self, req: SubscriptionRequest) -> Result<(), String> {
+        let added = req.addresses.len() + req.txids.len() + req.pro_tx_hashes.len();
+        if self.watched() + added > MAX_WATCHED_ITEMS {
+            return Err(format!(
+                "Subscription limit is {} addresses, txids and proTxHashes per connection",
+                MAX_WATCHED_ITEMS
+            ));
+        }
+
+        self.filtered = true;
+        self.topics.extend(req.topics);
+        self.addresses.extend(req.addresses);
+        self.txids.extend(req.txids);
+        self.pro_tx_hashes.extend(req.pro_tx_hashes);
+        Ok(())
+    }
+
+    pub fn unsubscribe(&mut self, req: SubscriptionRequest) {
+        self.filtered = true;
+        for topic in &req.topics {
+            self.topics.remove(topic);
+        }
+        for addr in &req.addresses {
+            self.addresses.remove(addr);
+        }
+        for txid in &req.txids {
+            self.txids.remove(txid);
+        }
+        for hash in &req.pro_tx_hashes {
+            self.pro_tx_hashes.remove(hash);
+        }
+    }
+
+    /// Whether the event should be delivered on this socket
+    pub fn matches(&mut self, event: &LiveEvent) -> bool {
+        if !self.filtered {
+            return true;
+        }
+
+        match event {
+            LiveEvent::NewBlock { .. }
+            | LiveEvent::BlockDisconnected { .. }
+            | LiveEvent::Reorg { .. } => self.topics.contains(&Topic::Blocks),
+            LiveEvent::MempoolUpdate { .. } => self.topics.contains(&Topic::Mempool),
+            LiveEvent::NewTransaction {
+                txid,
+                addresses,
+                pro_tx_hash,
+                ..
+            } => {
+                let watched = self.txids.contains(txid)
+                    || addresses.iter().any(|a| self.addresses.contains(a))
+                    || pro_tx_hash
+                        .as_ref()
+                        .is_some_and(|h| self.pro_tx_hashes.contains(h));
+                if watched && self.followed.len() < MAX_FOLLOWED_TXIDS {
+                    self.followed.insert(txid.clone());
+                }
+                watched || self.topics.contains(&Topic::Mempool)
+            }
+            LiveEvent::InstantLock { txid } => {
+                self.topics.contains(&Topic::Mempool)
+                    || self.txids.contains(txid)
+                    || self.followed.remove(txid)
+            }
+            LiveEvent::ChainLock { .. } => self.topics.contains(&Topic::Chainlocks),
+            LiveEvent::GovernanceObject { .. } | LiveEvent::GovernanceVote { .. } => {
+                self.topics.contains(&Topic::Governance)
+            }
+        }
+    }
+
+    fn ack(&self) -> ServerMessage {
+        let mut topics: Vec<Topic> = self.topics.iter().copied().collect();
+        topics.sort_by_key(|t| *t as u8);
+        ServerMessage::Subscribed {
+            topics,
+            addresses: self.addresses.len(),
+            txids: self.txids.len(),
+            pro_tx_hashes: self.pro_tx_hashes.len(),
+        }
+    }
+}
+
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
@@ -16,11 +166,16 @@ pub async fn websocket_handler(
 async fn handle_socket(mut socket: WebSocket, mut rx: broadcast::Receiver<LiveEvent>) {
     tracing::info!("WebSocket client connected");
 
+    let mut subscription = Subscription::default();
+
     loop {
         tokio::select! {
             event = rx.recv() => {
                 match event {
                     Ok(live_event) => {
+                        if !subscription.matches(&live_event) {
+                            continue;
+                        }
                         if let Ok(json) = serde_json::to_string(&live_event) {
                             if socket.send(Message::Text(json.into())).await.is_err() {
                                 break;
@@ -38,11 +193,21 @@ async fn handle_socket(mut socket: WebSocket, mut rx: broadcast::Receiver<LiveEv
             msg = socket.recv() => {
                 match msg {
                     Some(Ok(Message::Close(_))) | None => break,
+                    // The pong consumes `data`, so this can't become a match guard
+                    #[allow(clippy::collapsible_match)]
                     Some(Ok(Message::Ping(data))) => {
                         if socket.send(Message::Pong(data)).await.is_err() {
                             break;
                         }
                     }
+                    Some(Ok(Message::Text(text))) => {
+                        let reply = handle_client_message(&mut subscription, &text);
+                        if let Ok(json) = serde_json::to_string(&reply) {
+                            if socket.send(Message::Text(json.into())).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
                     _ => {} // Ignore other messages
                 }
             }
@@ -51,3 +216,25 @@ async fn handle_socket(mut socket: WebSocket, mut rx: broadcast::Receiver<LiveEv
 
     tracing::info!("WebSocket client disconnected");
 }
+
+fn handle_client_message(subscription: &mut Subscription, text: &str) -> ServerMessage {
+    let msg: ClientMessage = match serde_json::from_str(text) {
+        Ok(msg) => msg,
+        Err(e) => {
+            return ServerMessage::Error {
+                message: format!("Invalid message: {}", e),
+            }
+        }
+    };
+
+    match msg {
+        ClientMessage::Subscribe(req) => {
+            if let Err(message) = subscription.subscribe(req) {
+                return ServerMessage::Error { message };
+            }
+        }
+        ClientMessage::Unsubscribe(req) => subscription.unsubscribe(req),
+    }
+
+    subscription.ack()
+}
@@ -0,0 +1,167 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::models::fee::{FeeEstimate, FeesResponse};
+use crate::AppError;
+use crate::AppState;
+
+/// Targets reported when none are requested
+const DEFAULT_TARGETS: [u32; 8] = [1, 2, 3, 6, 12, 24, 48, 144];
+/// Longest target `estimatesmartfee` accepts
+const MAX_TARGET: u32 = 1008;
+/// Targets per request
+const MAX_TARGETS: usize = 16;
+/// Block size limit used to turn the mempool backlog into blocks
+const MAX_BLOCK_SIZE: u64 = 2_000_000;
+/// Blocks looked at for the recent-block fallback
+const RECENT_BLOCKS: u64 = 12;
+
+/// DASH per kB, as reported by the node, to duffs per byte
+fn duffs_per_byte(dash_per_kb: f64) -> f64 {
+    dash_per_kb * 1e5
+}
+
+/// Comma-separated confirmation targets, sorted and deduplicated
+pub(crate) fn parse_targets(targets: &str) -> Result<Vec<u32>, AppError> {
+    let mut parsed = targets
+        .split(',')
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .map(|t| match t.parse::<u32>() {
+            Ok(n) if (1..=MAX_TARGET).contains(&n) => Ok(n),
+            _ => Err(AppError::BadRequest(format!(
+                "Invalid target '{}'; targets are 1 to {} blocks",
+                t, MAX_TARGET
+            ))),
+        })
+        .collect::<Result<Vec<u32>, _>>()?;
+    parsed.sort_unstable();
+    parsed.dedup();
+    if parsed.is_empty() || parsed.len() > MAX_TARGETS {
+        return Err(AppError::BadRequest(format!(
+            "Give between 1 and {} targets",
+            MAX_TARGETS
+        )));
+    }
+    Ok(parsed)
+}
+
+#[derive(Deserialize)]
+pub struct FeesParams {
+    /// Comma-separated confirmation targets in blocks
+    pub targets: Option<String>,
+}
+
+pub async fn get_fees(
+    State(state): State<AppState>,
+    Query(params): Query<FeesParams>,
+) -> Result<Json<FeesResponse>, AppError> {
+    let targets = match params.targets {
+        Some(ref targets) => parse_targets(targets)?,
+        None => DEFAULT_TARGETS.to_vec(),
+    };
+    let key = targets
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    if let Some(cached) = state.cache.fees.get(&key).await {
+        return Ok(Json((*cached).clone()));
+    }
+
+    let requests: Vec<(u32, &str)> = targets
+        .iter()
+        .flat_map(|&t| [(t, "CONSERVATIVE"), (t, "ECONOMICAL")])
+        .collect();
+    let (estimates_res, info_res, pool_res, height_res) = tokio::join!(
+        state.rpc.estimate_smart_fees(&requests),
+        state.rpc.get_mempool_info(),
+        state.rpc.get_raw_mempool_verbose(),
+        state.rpc.get_block_count(),
+    );
+    let estimates = estimates_res?;
+    let info = info_res?;
+    let pool = pool_res?;
+    let height = height_res?;
+
+    let min_relay_fee = duffs_per_byte(info.minrelaytxfee);
+    let mempool_min_fee = duffs_per_byte(info.mempoolminfee);
+    let floor = min_relay_fee.max(mempool_min_fee);
+
+    // (fee rate, size) of waiting transactions, best paying first
+    let mut backlog: Vec<(f64, u64)> = pool
+        .values()
+        .filter(|e| e.size > 0)
+        .map(|e| (e.base_fee() * 1e8 / e.size as f64, e.size))
+        .collect();
+    backlog.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    // getblockstats needs -txindex for fee rates; blocks it fails on are skipped
+    let heights: Vec<u64> = (height.saturating_sub(RECENT_BLOCKS - 1)..=height).collect();
+    let mut rates: Vec<f64> = state
+        .rpc
+        .get_block_stats_batch(&heights)
+        .await?
+        .into_iter()
+        .filter_map(|stats| stats.ok())
+        .filter(|stats| stats.txs > 1)
+        .filter_map(|stats| stats.feerate_percentiles.get(2).copied())
+        .collect();
+    rates.sort_by(|a, b| a.total_cmp(b));
+    let recent_blocks_fee_rate = rates.get(rates.len() / 2).copied();
+
+    let estimates = targets
+        .iter()
+        .zip(estimates.chunks(2))
+        .map(|(&target, modes)| {
+            let rate = |i: usize| match modes.get(i) {
+                Some(Ok(estimate)) => estimate.feerate.map(duffs_per_byte),
+                _ => None,
+            };
+            let (conservative, economical) = (rate(0), rate(1));
+
+            // Rate of the first transaction that wouldn't fit in `target` full blocks
+            let capacity = target as u64 * MAX_BLOCK_SIZE;
+            let mut filled = 0u64;
+            let mempool = backlog.iter().find_map(|&(rate, size)| {
+                filled += size;
+                (filled > capacity).then_some(rate)
+            });
+
+            let (fee_rate, source) = match (economical.or(conservative), mempool) {
+                (Some(rate), _) => (rate, "node"),
+                (None, Some(rate)) => (rate, "mempool"),
+                (None, None) => match recent_blocks_fee_rate {
+                    Some(rate) => (rate, "blocks"),
+                    None => (floor, "min_relay"),
+                },
+            };
+            let (fee_rate, source) = if fee_rate < floor {
+                (floor, "min_relay")
+            } else {
+                (fee_rate, source)
+            };
+
+            FeeEstimate {
+                target,
+                conservative,
+                economical,
+                fee_rate,
+                source: source.to_string(),
+            }
+        })
+        .collect();
+
+    let response = FeesResponse {
+        height,
+        min_relay_fee,
+        mempool_min_fee,
+        recent_blocks_fee_rate,
+        mempool_bytes: info.bytes,
+        estimates,
+    };
+    state.cache.fees.insert(key, Arc::new(response.clone())).await;
+    Ok(Json(response))
+}
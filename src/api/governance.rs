@@ -22,7 +22,7 @@ pub async fn get_governance(
         .collect();
 
     // Sort by creation time, newest first
-    proposals.sort_by(|a, b| b.creation_time.cmp(&a.creation_time));
+    proposals.sort_by_key(|p| std::cmp::Reverse(p.creation_time));
 
     Ok(Json(GovernanceOverview {
         info: GovernanceInfo::from_rpc(&info),
@@ -3,7 +3,11 @@ use axum::response::IntoResponse;
 use axum::Json;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 
+use crate::models::pool::Miner;
+use crate::pools;
+use crate::rpc::types::{RpcAddressMempoolEntry, RpcTransaction};
 use crate::AppError;
 use crate::AppState;
 
@@ -21,21 +25,30 @@ pub async fn get_block(
         .map(|ids| ids.iter().map(|id| json!(id)).collect())
         .unwrap_or_default();
 
-    // Compute reward from coinbase output
-    let reward = if let Ok(full_block) = state.rpc.get_block(&hash, 2).await {
-        if let Some(txs) = full_block.transactions() {
-            if let Some(cb_tx) = txs.first() {
-                let total: f64 = cb_tx.vout.iter().map(|o| o.value).sum();
-                format!("{:.8}", total)
-            } else {
-                "0.00000000".to_string()
-            }
-        } else {
-            "0.00000000".to_string()
+    // Reward is the sum of the coinbase outputs. The coinbase is fetched through its
+    // block, without decoding the whole block or needing -txindex.
+    let coinbase: Vec<(String, String)> = block
+        .txids()
+        .and_then(|ids| ids.first().cloned())
+        .map(|txid| (txid, block.hash.clone()))
+        .into_iter()
+        .collect();
+    let (coinbase, miners) = tokio::join!(
+        state.rpc.get_block_transactions(&coinbase),
+        pools::block_miners(&state, std::slice::from_ref(&block.hash)),
+    );
+    let reward = match coinbase.ok().and_then(|txs| txs.into_iter().next()?.ok()) {
+        Some(cb_tx) => {
+            let total: f64 = cb_tx.vout.iter().map(|o| o.value).sum();
+            format!("{:.8}", total)
         }
-    } else {
-        "0.00000000".to_string()
+        None => "0.00000000".to_string(),
     };
+    let pool_info = miners
+        .ok()
+        .and_then(|m| m.into_iter().next())
+        .map(|m| insight_pool_info(&m))
+        .unwrap_or_else(|| json!({}));
 
     Ok(Json(json!({
         "hash": block.hash,
@@ -54,10 +67,18 @@ pub async fn get_block(
         "nextblockhash": block.next_block_hash,
         "reward": reward,
         "isMainChain": true,
-        "poolInfo": {}
+        "poolInfo": pool_info
     })))
 }
 
+/// Insight's `poolInfo`: `{}` for unknown miners
+fn insight_pool_info(miner: &Miner) -> Value {
+    match miner.pool {
+        Some(ref name) => json!({ "poolName": name, "url": miner.link }),
+        None => json!({}),
+    }
+}
+
 pub async fn get_block_index(
     State(state): State<AppState>,
     Path(height): Path<u64>,
@@ -81,11 +102,14 @@ pub struct BlocksParams {
     pub block_date: Option<String>,
 }
 
+/// Headers fetched per batch while walking back to the requested date
+const BLOCK_WALK_BATCH: u64 = 50;
+
 pub async fn get_blocks(
     State(state): State<AppState>,
     Query(params): Query<BlocksParams>,
 ) -> Result<Json<Value>, AppError> {
-    let limit = params.limit.unwrap_or(200).min(200);
+    let limit = params.limit.unwrap_or(200).min(state.config.limits.insight_blocks);
 
     // Get the current date info
     let tip = state.rpc.get_block_count().await?;
@@ -102,26 +126,31 @@ pub async fn get_blocks(
     // Walk backwards from tip finding blocks in the date range
     let mut blocks = Vec::new();
     let mut h = tip;
-    while h > 0 && blocks.len() < limit as usize {
-        let hash = state.rpc.get_block_hash(h).await?;
-        let header = state.rpc.get_block_header(&hash).await?;
-
-        if header.time < date_ts_start {
-            break;
-        }
+    'walk: while h > 0 && blocks.len() < limit as usize {
+        let low = h.saturating_sub(BLOCK_WALK_BATCH - 1).max(1);
+        let heights: Vec<u64> = (low..=h).rev().collect();
+        let hashes = state.rpc.get_block_hashes(&heights).await?;
+        let headers = state.rpc.get_block_headers(&hashes).await?;
+        let miners = pools::block_miners(&state, &hashes).await.unwrap_or_default();
+
+        for (i, header) in headers.into_iter().enumerate() {
+            if header.time < date_ts_start || blocks.len() >= limit as usize {
+                break 'walk;
+            }
 
-        if header.time < date_ts_end {
-            blocks.push(json!({
-                "height": header.height,
-                "size": header.size.unwrap_or(0),
-                "hash": header.hash,
-                "time": header.time,
-                "txlength": header.n_tx,
-                "poolInfo": {}
-            }));
+            if header.time < date_ts_end {
+                blocks.push(json!({
+                    "height": header.height,
+                    "size": header.size.unwrap_or(0),
+                    "hash": header.hash,
+                    "time": header.time,
+                    "txlength": header.n_tx,
+                    "poolInfo": miners.get(i).map(insight_pool_info).unwrap_or_else(|| json!({}))
+                }));
+            }
         }
 
-        h -= 1;
+        h = low - 1;
     }
 
     // Build pagination
@@ -199,26 +228,28 @@ pub async fn get_txs(
         let paged: Vec<Value> = txs.into_iter().skip(start).take(page_size).collect();
 
         return Ok(Json(json!({
-            "pagesTotal": (total + page_size - 1) / page_size,
+            "pagesTotal": total.div_ceil(page_size),
             "txs": paged
         })));
     }
 
     if let Some(ref address) = params.address {
-        let all_txids = state.rpc.get_address_txids(address).await?;
+        let all_txids = state.get_address_txids(address).await?;
         let total = all_txids.len();
         let start = (page as usize) * page_size;
-        let page_txids: Vec<&String> = all_txids.iter().rev().skip(start).take(page_size).collect();
+        let page_txids: Vec<String> = all_txids.iter().rev().skip(start).take(page_size).cloned().collect();
 
-        let mut txs = Vec::new();
-        for txid in page_txids {
-            if let Ok(tx) = state.rpc.get_raw_transaction(txid).await {
-                txs.push(format_insight_tx(&tx));
-            }
-        }
+        let txs: Vec<Value> = state
+            .rpc
+            .get_raw_transactions(&page_txids)
+            .await?
+            .iter()
+            .flatten()
+            .map(format_insight_tx)
+            .collect();
 
         return Ok(Json(json!({
-            "pagesTotal": (total + page_size - 1) / page_size,
+            "pagesTotal": total.div_ceil(page_size),
             "txs": txs
         })));
     }
@@ -255,13 +286,16 @@ pub async fn get_addr(
     Path(addr): Path<String>,
     Query(params): Query<AddrParams>,
 ) -> Result<Json<Value>, AppError> {
-    let (balance_res, txids_res) = tokio::join!(
-        state.rpc.get_address_balance(&addr),
-        state.rpc.get_address_txids(&addr),
+    let (balance_res, txids_res, mempool_res) = tokio::join!(
+        state.get_address_balance(&addr),
+        state.get_address_txids(&addr),
+        state.get_address_mempool(&addr),
     );
 
     let balance = balance_res?;
     let txids = txids_res?;
+    let mempool = mempool_res.unwrap_or_default();
+    let (unconfirmed_sat, unconfirmed_txs) = unconfirmed_summary(&mempool);
 
     let balance_dash = balance.balance as f64 / 1e8;
     let received_dash = balance.received as f64 / 1e8;
@@ -271,16 +305,23 @@ pub async fn get_addr(
 
     let no_tx_list = params.no_tx_list.unwrap_or(0);
     let from = params.from.unwrap_or(0) as usize;
-    let to = params.to.unwrap_or(tx_count as u32) as usize;
+    let to = params.to.unwrap_or((tx_count + unconfirmed_txs) as u32) as usize;
 
     let transactions = if no_tx_list == 1 {
         Value::Null
     } else {
-        let page: Vec<Value> = txids
-            .iter()
-            .rev()
+        // Unconfirmed transactions are listed ahead of confirmed ones, like Insight
+        let mut pending: Vec<&String> = Vec::new();
+        for entry in mempool.iter().rev() {
+            if !pending.contains(&&entry.txid) {
+                pending.push(&entry.txid);
+            }
+        }
+        let page: Vec<Value> = pending
+            .into_iter()
+            .chain(txids.iter().rev())
             .skip(from)
-            .take(to.saturating_sub(from).min(50))
+            .take(to.saturating_sub(from).min(state.config.limits.insight_txs as usize))
             .map(|id| json!(id))
             .collect();
         Value::Array(page)
@@ -294,10 +335,10 @@ pub async fn get_addr(
         "totalReceivedSat": balance.received,
         "totalSent": sent_dash,
         "totalSentSat": sent_sat,
-        "unconfirmedBalance": 0,
-        "unconfirmedBalanceSat": 0,
-        "unconfirmedTxApperances": 0,
-        "unconfirmedAppearances": 0,
+        "unconfirmedBalance": unconfirmed_sat as f64 / 1e8,
+        "unconfirmedBalanceSat": unconfirmed_sat,
+        "unconfirmedTxApperances": unconfirmed_txs,
+        "unconfirmedAppearances": unconfirmed_txs,
         "txApperances": tx_count,
         "txAppearances": tx_count,
     });
@@ -321,7 +362,7 @@ pub async fn get_addr_balance(
     State(state): State<AppState>,
     Path(addr): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let balance = state.rpc.get_address_balance(&addr).await?;
+    let balance = state.get_address_balance(&addr).await?;
     Ok(balance.balance.to_string())
 }
 
@@ -329,7 +370,7 @@ pub async fn get_addr_total_received(
     State(state): State<AppState>,
     Path(addr): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let balance = state.rpc.get_address_balance(&addr).await?;
+    let balance = state.get_address_balance(&addr).await?;
     Ok(balance.received.to_string())
 }
 
@@ -337,42 +378,106 @@ pub async fn get_addr_total_sent(
     State(state): State<AppState>,
     Path(addr): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let balance = state.rpc.get_address_balance(&addr).await?;
+    let balance = state.get_address_balance(&addr).await?;
     let sent = balance.received - balance.balance;
     Ok(sent.to_string())
 }
 
 pub async fn get_addr_unconfirmed_balance(
-    State(_state): State<AppState>,
-    Path(_addr): Path<String>,
+    State(state): State<AppState>,
+    Path(addr): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    Ok("0".to_string())
+    let mempool = state.get_address_mempool(&addr).await?;
+    let (unconfirmed, _) = unconfirmed_summary(&mempool);
+    Ok(unconfirmed.to_string())
 }
 
 pub async fn get_addr_utxo(
     State(state): State<AppState>,
     Path(addr): Path<String>,
 ) -> Result<Json<Value>, AppError> {
-    let utxos = state.rpc.get_address_utxos(&addr).await?;
     let tip = state.rpc.get_block_count().await?;
+    let items = utxo_items(&state, &addr, tip).await?;
+    Ok(Json(Value::Array(items)))
+}
 
-    let items: Vec<Value> = utxos
+/// Unspent outputs of an address in Insight's format. Outputs created in the mempool
+/// come first with `confirmations: 0`; confirmed outputs already spent there are left out.
+async fn utxo_items(state: &AppState, addr: &str, tip: u64) -> Result<Vec<Value>, AppError> {
+    let (utxos, mempool) = tokio::join!(
+        state.get_address_utxos(addr),
+        state.get_address_mempool(addr),
+    );
+    let utxos = utxos?;
+    let mempool = mempool.unwrap_or_default();
+
+    let spent: HashSet<(&str, u32)> = mempool
         .iter()
-        .map(|u| {
-            json!({
-                "address": u.address,
-                "txid": u.txid,
-                "vout": u.output_index,
-                "scriptPubKey": u.script,
-                "amount": u.satoshis as f64 / 1e8,
-                "satoshis": u.satoshis,
-                "height": u.height,
-                "confirmations": tip.saturating_sub(u.height) + 1
-            })
-        })
+        .filter_map(|e| Some((e.prevtxid.as_deref()?, e.prevout?)))
         .collect();
 
-    Ok(Json(Value::Array(items)))
+    let created: Vec<&RpcAddressMempoolEntry> = mempool
+        .iter()
+        .filter(|e| e.prevtxid.is_none() && !spent.contains(&(e.txid.as_str(), e.index)))
+        .collect();
+
+    // getaddressmempool doesn't report the script
+    let mut txids: Vec<String> = created.iter().map(|e| e.txid.clone()).collect();
+    txids.sort_unstable();
+    txids.dedup();
+    let txs: HashMap<String, RpcTransaction> = state
+        .rpc
+        .get_raw_transactions(&txids)
+        .await?
+        .into_iter()
+        .flatten()
+        .map(|tx| (tx.txid.clone(), tx))
+        .collect();
+
+    let mut items = Vec::new();
+    for entry in created {
+        let Some(output) = txs
+            .get(&entry.txid)
+            .and_then(|tx| tx.vout.iter().find(|o| o.n == entry.index))
+        else {
+            continue;
+        };
+        items.push(json!({
+            "address": entry.address,
+            "txid": entry.txid,
+            "vout": entry.index,
+            "scriptPubKey": output.script_pub_key.hex,
+            "amount": entry.satoshis as f64 / 1e8,
+            "satoshis": entry.satoshis,
+            "ts": entry.timestamp,
+            "confirmations": 0
+        }));
+    }
+
+    for u in &utxos {
+        if spent.contains(&(u.txid.as_str(), u.output_index)) {
+            continue;
+        }
+        items.push(json!({
+            "address": u.address,
+            "txid": u.txid,
+            "vout": u.output_index,
+            "scriptPubKey": u.script,
+            "amount": u.satoshis as f64 / 1e8,
+            "satoshis": u.satoshis,
+            "height": u.height,
+            "confirmations": tip.saturating_sub(u.height) + 1
+        }));
+    }
+
+    Ok(items)
+}
+
+/// Unconfirmed balance in satoshis and number of mempool transactions
+fn unconfirmed_summary(entries: &[RpcAddressMempoolEntry]) -> (i64, usize) {
+    let balance = entries.iter().map(|e| e.satoshis).sum();
+    let txs: HashSet<&str> = entries.iter().map(|e| e.txid.as_str()).collect();
+    (balance, txs.len())
 }
 
 // Multi-address endpoints
@@ -389,19 +494,8 @@ pub async fn get_addrs_utxo(
         if addr.is_empty() {
             continue;
         }
-        if let Ok(utxos) = state.rpc.get_address_utxos(addr).await {
-            for u in &utxos {
-                all_utxos.push(json!({
-                    "address": u.address,
-                    "txid": u.txid,
-                    "vout": u.output_index,
-                    "scriptPubKey": u.script,
-                    "amount": u.satoshis as f64 / 1e8,
-                    "satoshis": u.satoshis,
-                    "height": u.height,
-                    "confirmations": tip.saturating_sub(u.height) + 1
-                }));
-            }
+        if let Ok(utxos) = utxo_items(&state, addr, tip).await {
+            all_utxos.extend(utxos);
         }
     }
 
@@ -427,19 +521,8 @@ pub async fn post_addrs_utxo(
         if addr.is_empty() {
             continue;
         }
-        if let Ok(utxos) = state.rpc.get_address_utxos(addr).await {
-            for u in &utxos {
-                all_utxos.push(json!({
-                    "address": u.address,
-                    "txid": u.txid,
-                    "vout": u.output_index,
-                    "scriptPubKey": u.script,
-                    "amount": u.satoshis as f64 / 1e8,
-                    "satoshis": u.satoshis,
-                    "height": u.height,
-                    "confirmations": tip.saturating_sub(u.height) + 1
-                }));
-            }
+        if let Ok(utxos) = utxo_items(&state, addr, tip).await {
+            all_utxos.extend(utxos);
         }
     }
 
@@ -460,7 +543,7 @@ pub async fn get_addrs_txs(
         if addr.is_empty() {
             continue;
         }
-        if let Ok(txids) = state.rpc.get_address_txids(addr).await {
+        if let Ok(txids) = state.get_address_txids(addr).await {
             for txid in txids {
                 if !all_txids.contains(&txid) {
                     all_txids.push(txid);
@@ -470,14 +553,16 @@ pub async fn get_addrs_txs(
     }
 
     let total = all_txids.len();
-    let page_txids: Vec<&String> = all_txids.iter().rev().skip(from).take(to - from).collect();
+    let page_txids: Vec<String> = all_txids.iter().rev().skip(from).take(to.saturating_sub(from).min(state.config.limits.insight_txs as usize)).cloned().collect();
 
-    let mut items = Vec::new();
-    for txid in page_txids {
-        if let Ok(tx) = state.rpc.get_raw_transaction(txid).await {
-            items.push(format_insight_tx(&tx));
-        }
-    }
+    let items: Vec<Value> = state
+        .rpc
+        .get_raw_transactions(&page_txids)
+        .await?
+        .iter()
+        .flatten()
+        .map(format_insight_tx)
+        .collect();
 
     Ok(Json(json!({
         "totalItems": total,
@@ -500,7 +585,7 @@ pub async fn post_addrs_txs(
         if addr.is_empty() {
             continue;
         }
-        if let Ok(txids) = state.rpc.get_address_txids(addr).await {
+        if let Ok(txids) = state.get_address_txids(addr).await {
             for txid in txids {
                 if !all_txids.contains(&txid) {
                     all_txids.push(txid);
@@ -510,14 +595,16 @@ pub async fn post_addrs_txs(
     }
 
     let total = all_txids.len();
-    let page_txids: Vec<&String> = all_txids.iter().rev().skip(from).take(to - from).collect();
+    let page_txids: Vec<String> = all_txids.iter().rev().skip(from).take(to.saturating_sub(from).min(state.config.limits.insight_txs as usize)).cloned().collect();
 
-    let mut items = Vec::new();
-    for txid in page_txids {
-        if let Ok(tx) = state.rpc.get_raw_transaction(txid).await {
-            items.push(format_insight_tx(&tx));
-        }
-    }
+    let items: Vec<Value> = state
+        .rpc
+        .get_raw_transactions(&page_txids)
+        .await?
+        .iter()
+        .flatten()
+        .map(format_insight_tx)
+        .collect();
 
     Ok(Json(json!({
         "totalItems": total,
@@ -535,7 +622,7 @@ pub async fn get_addrs_balance(
     for addr in addrs.split(',') {
         let addr = addr.trim();
         if addr.is_empty() { continue; }
-        if let Ok(b) = state.rpc.get_address_balance(addr).await {
+        if let Ok(b) = state.get_address_balance(addr).await {
             total += b.balance;
         }
     }
@@ -550,7 +637,7 @@ pub async fn get_addrs_total_received(
     for addr in addrs.split(',') {
         let addr = addr.trim();
         if addr.is_empty() { continue; }
-        if let Ok(b) = state.rpc.get_address_balance(addr).await {
+        if let Ok(b) = state.get_address_balance(addr).await {
             total += b.received;
         }
     }
@@ -565,7 +652,7 @@ pub async fn get_addrs_total_sent(
     for addr in addrs.split(',') {
         let addr = addr.trim();
         if addr.is_empty() { continue; }
-        if let Ok(b) = state.rpc.get_address_balance(addr).await {
+        if let Ok(b) = state.get_address_balance(addr).await {
             total += b.received - b.balance;
         }
     }
@@ -573,10 +660,18 @@ pub async fn get_addrs_total_sent(
 }
 
 pub async fn get_addrs_unconfirmed_balance(
-    State(_state): State<AppState>,
-    Path(_addrs): Path<String>,
+    State(state): State<AppState>,
+    Path(addrs): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    Ok("0".to_string())
+    let mut total: i64 = 0;
+    for addr in addrs.split(',') {
+        let addr = addr.trim();
+        if addr.is_empty() { continue; }
+        if let Ok(mempool) = state.get_address_mempool(addr).await {
+            total += unconfirmed_summary(&mempool).0;
+        }
+    }
+    Ok(total.to_string())
 }
 
 // ============ Governance ============
@@ -843,22 +938,29 @@ pub async fn peer(
 
 #[derive(Deserialize)]
 pub struct EstimateFeeParams {
+    /// One target or several, comma-separated
     #[serde(rename = "nbBlocks")]
-    pub nb_blocks: Option<u32>,
+    pub nb_blocks: Option<String>,
 }
 
 pub async fn estimate_fee(
     State(state): State<AppState>,
     Query(params): Query<EstimateFeeParams>,
 ) -> Result<Json<Value>, AppError> {
-    let n = params.nb_blocks.unwrap_or(2);
-    let result = state.rpc.estimate_fee(n).await?;
-    // estimatesmartfee returns {feerate: x, blocks: y}
-    let fee = result
-        .get("feerate")
-        .and_then(|f| f.as_f64())
-        .unwrap_or(-1.0);
-    Ok(Json(json!({ n.to_string(): fee })))
+    let targets = super::fees::parse_targets(params.nb_blocks.as_deref().unwrap_or("2"))?;
+    let requests: Vec<(u32, &str)> = targets.iter().map(|&n| (n, "CONSERVATIVE")).collect();
+    let estimates = state.rpc.estimate_smart_fees(&requests).await?;
+
+    // DASH per kB, or -1 where the node has no estimate
+    let fees: serde_json::Map<String, Value> = targets
+        .iter()
+        .zip(estimates)
+        .map(|(n, estimate)| {
+            let fee = estimate.ok().and_then(|e| e.feerate).unwrap_or(-1.0);
+            (n.to_string(), json!(fee))
+        })
+        .collect();
+    Ok(Json(Value::Object(fees)))
 }
 
 // ============ Helpers ============
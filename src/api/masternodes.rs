@@ -1,8 +1,20 @@
 use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
 
-use crate::models::masternode::{MasternodeDetail, MasternodeSummary};
+use crate::geoip::GeoIpDb;
+use crate::masternodes;
+use crate::models::masternode::{
+    AsnCount, MasternodeCount, MasternodeDetail, MasternodeHistoryResponse,
+    MasternodePaymentsResponse, MasternodeStatsResponse, MasternodeSummary, PaymentQueueResponse,
+    PoseBucket, VersionCount,
+};
 use crate::AppError;
 use crate::AppState;
 
@@ -13,6 +25,14 @@ pub struct MasternodeListParams {
     #[serde(rename = "type")]
     pub mn_type: Option<String>,
     pub status: Option<String>,
+    /// IP, `IP:port`, an IP prefix ending in a dot, an address or a proTxHash prefix
+    pub search: Option<String>,
+    /// Any field of the list entries
+    pub sort: Option<String>,
+    /// `asc` or `desc`
+    pub order: Option<String>,
+    /// `json` or `csv`
+    pub format: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -23,19 +43,101 @@ pub struct MasternodeListResponse {
     pub pages: u32,
 }
 
+type MasternodeOrder = fn(&MasternodeSummary, &MasternodeSummary) -> Ordering;
+
+/// Sort order for a field name, or None for an unknown field
+fn sort_key(field: &str) -> Option<MasternodeOrder> {
+    let key: MasternodeOrder = match field {
+        "pro_tx_hash" => |a, b| a.pro_tx_hash.cmp(&b.pro_tx_hash),
+        "mn_type" | "type" => |a, b| a.mn_type.cmp(&b.mn_type),
+        "status" => |a, b| a.status.cmp(&b.status),
+        // Numerically by IP where the service parses
+        "service" => |a, b| {
+            let a_addr = a.service.parse::<SocketAddr>().ok();
+            let b_addr = b.service.parse::<SocketAddr>().ok();
+            a_addr.cmp(&b_addr).then_with(|| a.service.cmp(&b.service))
+        },
+        "pose_penalty" => |a, b| a.pose_penalty.cmp(&b.pose_penalty),
+        "last_paid_block" => |a, b| a.last_paid_block.cmp(&b.last_paid_block),
+        "last_paid_time" => |a, b| a.last_paid_time.cmp(&b.last_paid_time),
+        "registered_height" => |a, b| a.registered_height.cmp(&b.registered_height),
+        "consecutive_payments" => |a, b| a.consecutive_payments.cmp(&b.consecutive_payments),
+        "collateral_address" => |a, b| a.collateral_address.cmp(&b.collateral_address),
+        "payout_address" => |a, b| a.payout_address.cmp(&b.payout_address),
+        "owner_address" => |a, b| a.owner_address.cmp(&b.owner_address),
+        "voting_address" => |a, b| a.voting_address.cmp(&b.voting_address),
+        "operator_reward" => |a, b| {
+            a.operator_reward
+                .partial_cmp(&b.operator_reward)
+                .unwrap_or(Ordering::Equal)
+        },
+        "platform_node_id" => |a, b| a.platform_node_id.cmp(&b.platform_node_id),
+        "platform_http_port" => |a, b| a.platform_http_port.cmp(&b.platform_http_port),
+        "platform_p2p_port" => |a, b| a.platform_p2p_port.cmp(&b.platform_p2p_port),
+        "is_platform_banned" => |a, b| a.is_platform_banned.cmp(&b.is_platform_banned),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Whether a masternode matches `/api/masternodes?search=`
+fn matches_search(mn: &MasternodeSummary, search: &str) -> bool {
+    let host = mn
+        .service
+        .parse::<SocketAddr>()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+    mn.service == search
+        || host == search
+        || (search.ends_with('.') && host.starts_with(search))
+        || mn.pro_tx_hash.starts_with(&search.to_lowercase())
+        || [
+            &mn.payout_address,
+            &mn.owner_address,
+            &mn.voting_address,
+            &mn.collateral_address,
+        ]
+        .iter()
+        .any(|a| a.as_str() == search)
+}
+
 pub async fn list_masternodes(
     State(state): State<AppState>,
     Query(params): Query<MasternodeListParams>,
-) -> Result<Json<MasternodeListResponse>, AppError> {
+) -> Result<Response, AppError> {
     let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(50).min(200);
-
-    let list = state.rpc.get_masternode_list().await?;
+    let limit = params
+        .limit
+        .unwrap_or(50)
+        .clamp(1, state.config.limits.masternodes_page);
+    let csv = match params.format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unknown format '{}'; use json or csv",
+                other
+            )))
+        }
+    };
+    let descending = match params.order.as_deref().unwrap_or("asc") {
+        "asc" => false,
+        "desc" => true,
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unknown order '{}'; use asc or desc",
+                other
+            )))
+        }
+    };
+    let key = match params.sort.as_deref() {
+        Some(field) => Some(sort_key(field).ok_or_else(|| {
+            AppError::BadRequest(format!("Unknown sort '{}'; use a masternode field name", field))
+        })?),
+        None => None,
+    };
 
-    let mut masternodes: Vec<MasternodeSummary> = list
-        .values()
-        .map(MasternodeSummary::from_list_entry)
-        .collect();
+    let mut masternodes = masternode_list(&state).await?;
 
     // Filter by type
     if let Some(ref mn_type) = params.mn_type {
@@ -51,19 +153,50 @@ pub async fn list_masternodes(
         }
     }
 
-    // Sort: enabled first, then by PoSe penalty
-    masternodes.sort_by(|a, b| {
-        let a_enabled = a.status == "ENABLED";
-        let b_enabled = b.status == "ENABLED";
-        b_enabled
-            .cmp(&a_enabled)
-            .then(a.pose_penalty.cmp(&b.pose_penalty))
-    });
+    if let Some(search) = params.search.as_deref().map(str::trim) {
+        if !search.is_empty() {
+            masternodes.retain(|mn| matches_search(mn, search));
+        }
+    }
+
+    match key {
+        Some(key) => masternodes.sort_by(|a, b| {
+            let ordering = key(a, b).then_with(|| a.pro_tx_hash.cmp(&b.pro_tx_hash));
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }),
+        // Enabled first, then by PoSe penalty
+        None => masternodes.sort_by(|a, b| {
+            let a_enabled = a.status == "ENABLED";
+            let b_enabled = b.status == "ENABLED";
+            b_enabled
+                .cmp(&a_enabled)
+                .then(a.pose_penalty.cmp(&b.pose_penalty))
+        }),
+    }
+
+    // The export has every match, not one page
+    if csv {
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"masternodes.csv\"",
+                ),
+            ],
+            to_csv(&masternodes),
+        )
+            .into_response());
+    }
 
     let total = masternodes.len();
     let pages = ((total as f64) / limit as f64).ceil() as u32;
 
-    let start = ((page - 1) * limit) as usize;
+    let start = (page as usize - 1).saturating_mul(limit as usize);
     let paginated: Vec<MasternodeSummary> = masternodes
         .into_iter()
         .skip(start)
@@ -75,13 +208,326 @@ pub async fn list_masternodes(
         total,
         page,
         pages,
-    }))
+    })
+    .into_response())
+}
+
+const CSV_HEADER: &str = "pro_tx_hash,mn_type,status,service,pose_penalty,last_paid_block,\
+last_paid_time,registered_height,consecutive_payments,collateral_address,payout_address,\
+owner_address,voting_address,operator_reward,platform_node_id,platform_http_port,\
+platform_p2p_port,is_platform_banned";
+
+/// Masternodes as CSV, one row each under `CSV_HEADER`. Missing values are left empty.
+fn to_csv(masternodes: &[MasternodeSummary]) -> String {
+    fn field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+    fn opt<T: ToString>(value: &Option<T>) -> String {
+        value.as_ref().map(T::to_string).unwrap_or_default()
+    }
+
+    let mut out = String::from(CSV_HEADER);
+    out.push_str("\r\n");
+    for mn in masternodes {
+        let row = [
+            field(&mn.pro_tx_hash),
+            field(&mn.mn_type),
+            field(&mn.status),
+            field(&mn.service),
+            mn.pose_penalty.to_string(),
+            mn.last_paid_block.to_string(),
+            mn.last_paid_time.to_string(),
+            opt(&mn.registered_height),
+            mn.consecutive_payments.to_string(),
+            field(&mn.collateral_address),
+            field(&mn.payout_address),
+            field(&mn.owner_address),
+            field(&mn.voting_address),
+            opt(&mn.operator_reward),
+            field(&opt(&mn.platform_node_id)),
+            opt(&mn.platform_http_port),
+            opt(&mn.platform_p2p_port),
+            opt(&mn.is_platform_banned),
+        ];
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Current masternode list, shared by the handlers that need all of it. Registration
+/// details come from `protx list`; without it the list is still returned, just without them.
+pub(crate) async fn masternode_list(state: &AppState) -> Result<Vec<MasternodeSummary>, AppError> {
+    if let Some(cached) = state.cache.masternode_list.get("list").await {
+        return Ok(cached);
+    }
+
+    let (list, protx) = tokio::join!(
+        state.rpc.get_masternode_list(),
+        masternodes::protx_list(state),
+    );
+    let list = list?;
+    let protx = protx.map(|(_, protx)| protx).unwrap_or_default();
+    let by_hash: HashMap<&str, _> = protx
+        .iter()
+        .map(|p| (p.pro_tx_hash.as_str(), p))
+        .collect();
+
+    let masternodes: Vec<MasternodeSummary> = list
+        .values()
+        .map(|entry| {
+            let mut mn = MasternodeSummary::from_list_entry(entry);
+            if let Some(protx) = by_hash.get(entry.pro_tx_hash.as_str()) {
+                mn.merge_protx(protx);
+            }
+            mn
+        })
+        .collect();
+
+    state
+        .cache
+        .masternode_list
+        .insert("list".to_string(), masternodes.clone())
+        .await;
+    Ok(masternodes)
+}
+
+/// Count occurrences of each name, most common first
+fn tally(names: impl IntoIterator<Item = String>) -> Vec<MasternodeCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for name in names {
+        *counts.entry(name).or_default() += 1;
+    }
+    let mut counts: Vec<MasternodeCount> = counts
+        .into_iter()
+        .map(|(name, count)| MasternodeCount { name, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts
+}
+
+pub async fn get_masternode_stats(
+    State(state): State<AppState>,
+) -> Result<Json<MasternodeStatsResponse>, AppError> {
+    if let Some(cached) = state.cache.masternode_stats.get("stats").await {
+        return Ok(Json((*cached).clone()));
+    }
+
+    let (masternodes, peers) = tokio::join!(masternode_list(&state), state.rpc.get_peer_info());
+    let masternodes = masternodes?;
+    // Versions are extra; without peer info the rest still stands
+    let peers = peers.unwrap_or_default();
+
+    // Core bans once the penalty passes the number of valid masternodes
+    let max = masternodes
+        .iter()
+        .filter(|mn| mn.status != "POSE_BANNED")
+        .count() as u32;
+    let mut bounds = vec![0, 1, max / 4, max / 2, max * 3 / 4, max + 1];
+    bounds.sort_unstable();
+    bounds.dedup();
+    let mut pose_histogram: Vec<PoseBucket> = bounds
+        .iter()
+        .enumerate()
+        .map(|(i, &min)| PoseBucket {
+            min,
+            max: bounds.get(i + 1).copied(),
+            count: 0,
+            regular: 0,
+            evo: 0,
+        })
+        .collect();
+    for mn in &masternodes {
+        let i = bounds.iter().rposition(|&b| mn.pose_penalty >= b).unwrap_or(0);
+        let bucket = &mut pose_histogram[i];
+        bucket.count += 1;
+        match mn.mn_type.as_str() {
+            "Evo" => bucket.evo += 1,
+            _ => bucket.regular += 1,
+        }
+    }
+
+    // A masternode's peer is the one that proved its proTxHash, or else one at its service
+    let mut by_hash: HashMap<&str, (u32, &str)> = HashMap::new();
+    let mut by_addr: HashMap<&str, (u32, &str)> = HashMap::new();
+    for peer in &peers {
+        let version = (peer.version, peer.subver.as_str());
+        match peer.verified_proregtx_hash.as_deref() {
+            Some(hash) => by_hash.insert(hash, version),
+            None => by_addr.insert(peer.addr.as_str(), version),
+        };
+    }
+    let mut versions: HashMap<(u32, &str), usize> = HashMap::new();
+    for mn in &masternodes {
+        let version = by_hash
+            .get(mn.pro_tx_hash.as_str())
+            .or_else(|| by_addr.get(mn.service.as_str()));
+        if let Some(&version) = version {
+            *versions.entry(version).or_default() += 1;
+        }
+    }
+    let versions_known = versions.values().sum();
+    let mut versions: Vec<VersionCount> = versions
+        .into_iter()
+        .map(|((protocol_version, user_agent), count)| VersionCount {
+            protocol_version,
+            user_agent: user_agent.to_string(),
+            count,
+        })
+        .collect();
+    versions.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.protocol_version.cmp(&a.protocol_version))
+            .then_with(|| a.user_agent.cmp(&b.user_agent))
+    });
+
+    let (located, countries, asns) = match state.geoip {
+        Some(ref geoip) => {
+            let (located, countries, asns) = locate(geoip, &masternodes);
+            (Some(located), Some(countries), Some(asns))
+        }
+        None => (None, None, None),
+    };
+
+    let stats = Arc::new(MasternodeStatsResponse {
+        total: masternodes.len(),
+        by_status: tally(masternodes.iter().map(|mn| mn.status.clone())),
+        by_type: tally(masternodes.iter().map(|mn| mn.mn_type.clone())),
+        pose_max_penalty: max,
+        pose_histogram,
+        versions_known,
+        versions,
+        located,
+        countries,
+        asns,
+    });
+    state
+        .cache
+        .masternode_stats
+        .insert("stats".to_string(), stats.clone())
+        .await;
+    Ok(Json((*stats).clone()))
+}
+
+/// Masternodes found in the geoip table, and their breakdown by country and AS
+fn locate(
+    geoip: &GeoIpDb,
+    masternodes: &[MasternodeSummary],
+) -> (usize, Vec<MasternodeCount>, Vec<AsnCount>) {
+    let places: Vec<_> = masternodes
+        .iter()
+        .map(|mn| {
+            let addr = mn.service.parse::<SocketAddr>().ok()?;
+            geoip.lookup(addr.ip())
+        })
+        .collect();
+    let located = places.iter().flatten().count();
+    let countries = tally(places.iter().map(|p| {
+        p.as_ref()
+            .and_then(|p| p.country.clone())
+            .unwrap_or_else(|| "unknown".to_string())
+    }));
+    let mut asns: HashMap<(Option<u32>, Option<String>), usize> = HashMap::new();
+    for place in places {
+        let place = place.unwrap_or_default();
+        *asns.entry((place.asn, place.name)).or_default() += 1;
+    }
+    let mut asns: Vec<AsnCount> = asns
+        .into_iter()
+        .map(|((asn, name), count)| AsnCount { asn, name, count })
+        .collect();
+    asns.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.asn.cmp(&b.asn)));
+    (located, countries, asns)
 }
 
 pub async fn get_masternode(
     State(state): State<AppState>,
     Path(protxhash): Path<String>,
 ) -> Result<Json<MasternodeDetail>, AppError> {
+    let (protx, quorums) = tokio::join!(
+        state.rpc.get_protx_info(&protxhash),
+        super::quorums::active_quorums(&state),
+    );
+    let mut detail = MasternodeDetail::from_protx(&protx?);
+
+    // Membership is extra; the node's own state is still worth returning without it
+    if let Ok(quorums) = quorums {
+        detail.quorums = quorums
+            .iter()
+            .filter_map(|q| q.membership(&detail.pro_tx_hash))
+            .collect();
+    }
+    Ok(Json(detail))
+}
+
+pub async fn get_masternode_history(
+    State(state): State<AppState>,
+    Path(protxhash): Path<String>,
+) -> Result<Json<MasternodeHistoryResponse>, AppError> {
+    let history = masternodes::history::history(&state, &protxhash).await?;
+    Ok(Json((*history).clone()))
+}
+
+#[derive(Deserialize)]
+pub struct NextPaymentsParams {
+    pub limit: Option<u32>,
+}
+
+pub async fn next_payments(
+    State(state): State<AppState>,
+    Query(params): Query<NextPaymentsParams>,
+) -> Result<Json<PaymentQueueResponse>, AppError> {
+    let limit = params.limit.unwrap_or(20).min(state.config.limits.payments_page);
+    let queue = masternodes::queue::next_payees(&state, limit as usize).await?;
+    Ok(Json(queue))
+}
+
+#[derive(Deserialize)]
+pub struct MasternodePaymentsParams {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+    /// Only payments below this height, from a previous response's `next_before`
+    pub before: Option<u64>,
+}
+
+pub async fn get_masternode_payments(
+    State(state): State<AppState>,
+    Path(protxhash): Path<String>,
+    Query(params): Query<MasternodePaymentsParams>,
+) -> Result<Json<MasternodePaymentsResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params
+        .limit
+        .unwrap_or(50)
+        .clamp(1, state.config.limits.payments_page);
+
     let protx = state.rpc.get_protx_info(&protxhash).await?;
-    Ok(Json(MasternodeDetail::from_protx(&protx)))
+    let scan = masternodes::payouts::payouts(&state, &protx, params.before).await?;
+    let payments = &scan.payments;
+
+    let total = payments.len();
+    let pages = ((total as f64) / limit as f64).ceil() as u32;
+    let start = (page as usize - 1).saturating_mul(limit as usize);
+
+    Ok(Json(MasternodePaymentsResponse {
+        pro_tx_hash: protx.pro_tx_hash.clone(),
+        payout_address: protx.state.payout_address.clone(),
+        operator_reward: protx.operator_reward,
+        total,
+        total_amount: payments.iter().map(|p| p.amount).sum(),
+        page,
+        pages,
+        payments: payments
+            .iter()
+            .skip(start)
+            .take(limit as usize)
+            .cloned()
+            .collect(),
+        next_before: scan.next_before,
+    }))
 }
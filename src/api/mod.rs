@@ -1,38 +1,108 @@
 mod addresses;
 mod blocks;
+mod charts;
 mod events;
+mod fees;
 mod governance;
 pub mod insight;
 mod masternodes;
 mod network;
+mod platform;
+mod pools;
+mod quorums;
+mod ratelimit;
 mod search;
+pub mod socketio;
 mod transactions;
 
+use axum::http::{HeaderValue, Method};
+use axum::middleware;
+use axum::response::Redirect;
 use axum::routing::{get, post};
 use axum::Router;
+use std::sync::Arc;
 use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 
+use crate::config::ServerConfig;
 use crate::AppState;
 
-pub fn build_router(state: AppState) -> Router {
-    let static_dir = state.config.server.static_dir.clone();
+/// Serve every network under `/{network}/...`; the first one is also served at the root
+pub fn build_router(networks: Vec<AppState>, server: &ServerConfig) -> Router {
+    let static_dir = server.static_dir.clone();
     let index_path = format!("{}/index.html", &static_dir);
 
+    let limiter = match server.rate_limit_per_minute {
+        0 => None,
+        per_minute => Some(Arc::new(ratelimit::RateLimiter::new(per_minute))),
+    };
+
+    let directory = Arc::new(networks.clone());
+    let mut router = Router::new()
+        .route("/api/networks", get(network::list_networks))
+        .with_state(directory);
+
+    for state in &networks {
+        let prefix = format!("/{}", state.config.profile);
+        let frontend = format!("{}/", prefix);
+        router = router
+            .nest(&prefix, network_routes(state.clone(), limiter.clone()))
+            // `/{network}/` loads the frontend, which picks the network from the path
+            .route_service(&frontend, ServeFile::new(&index_path))
+            .route(&prefix, get(move || async move { Redirect::permanent(&frontend) }));
+    }
+    router = router.merge(network_routes(networks[0].clone(), limiter));
+
+    let router = router
+        .fallback_service(
+            ServeDir::new(&static_dir)
+                .not_found_service(ServeFile::new(index_path)),
+        )
+        .layer(CompressionLayer::new());
+
+    match cors_layer(&server.cors_origins) {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
+}
+
+/// API, Insight and Socket.IO routes of one network
+fn network_routes(state: AppState, limiter: Option<Arc<ratelimit::RateLimiter>>) -> Router {
     let api_routes = Router::new()
         .route("/status", get(network::status))
         .route("/blocks", get(blocks::list_blocks))
         .route("/block/{hash_or_height}", get(blocks::get_block))
         .route("/tx/{txid}", get(transactions::get_transaction))
         .route("/address/{address}", get(addresses::get_address))
+        .route("/richlist", get(addresses::get_richlist))
         .route("/masternodes", get(masternodes::list_masternodes))
+        .route("/masternodes/stats", get(masternodes::get_masternode_stats))
+        .route(
+            "/masternodes/payments/next",
+            get(masternodes::next_payments),
+        )
         .route(
             "/masternode/{protxhash}",
             get(masternodes::get_masternode),
         )
+        .route(
+            "/masternode/{protxhash}/history",
+            get(masternodes::get_masternode_history),
+        )
+        .route(
+            "/masternode/{protxhash}/payments",
+            get(masternodes::get_masternode_payments),
+        )
+        .route("/quorums", get(quorums::list_quorums))
+        .route("/quorum/{quorum_type}/{hash}", get(quorums::get_quorum))
+        .route("/platform/nodes", get(platform::list_nodes))
         .route("/governance", get(governance::get_governance))
         .route("/network", get(network::get_network))
+        .route("/charts/{metric}", get(charts::get_chart))
+        .route("/pools", get(pools::get_pools))
         .route("/mempool", get(network::get_mempool))
+        .route("/fees", get(fees::get_fees))
         .route("/search", get(search::search))
         .route("/ws", get(events::websocket_handler));
 
@@ -82,13 +152,42 @@ pub fn build_router(state: AppState) -> Router {
         .route("/peer", get(insight::peer))
         .route("/utils/estimatefee", get(insight::estimate_fee));
 
+    let (api_routes, insight_routes) = match limiter {
+        Some(limiter) => {
+            let layer = middleware::from_fn_with_state(limiter, ratelimit::limit);
+            (api_routes.layer(layer.clone()), insight_routes.layer(layer))
+        }
+        None => (api_routes, insight_routes),
+    };
+
     Router::new()
         .nest("/api", api_routes)
         .nest("/insight-api", insight_routes)
-        .fallback_service(
-            ServeDir::new(&static_dir)
-                .not_found_service(ServeFile::new(index_path)),
+        // Insight Socket.IO rooms for legacy wallet clients
+        .route(
+            "/socket.io/",
+            get(socketio::get_handler).post(socketio::post_handler),
         )
-        .layer(CompressionLayer::new())
         .with_state(state)
 }
+
+fn cors_layer(origins: &[String]) -> Option<CorsLayer> {
+    if origins.is_empty() {
+        return None;
+    }
+    let allow_origin = if origins.iter().any(|o| o == "*") {
+        AllowOrigin::from(Any)
+    } else {
+        let origins: Vec<HeaderValue> = origins
+            .iter()
+            .filter_map(|o| HeaderValue::from_str(o.trim_end_matches('/')).ok())
+            .collect();
+        AllowOrigin::list(origins)
+    };
+    Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers(Any),
+    )
+}
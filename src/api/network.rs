@@ -1,8 +1,16 @@
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::Json;
-use serde::Serialize;
+use futures::future::join_all;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::sync::Arc;
 
-use crate::models::network::{MasternodeCountInfo, NetworkOverview, StatusResponse};
+use crate::models::block::TxTypeCount;
+use crate::models::network::{
+    MasternodeCountInfo, MempoolBucket, MempoolEntry, MempoolResponse, NetworkListResponse,
+    NetworkOverview, NetworkSummary, StatusResponse,
+};
+use crate::models::transaction::tx_type_label;
 use crate::AppError;
 use crate::AppState;
 
@@ -123,36 +131,225 @@ pub async fn get_network(
             evo_total: mn_count.detailed.evo.total,
             evo_enabled: mn_count.detailed.evo.enabled,
         },
+        rpc_backends: state.rpc.backend_status(),
     }))
 }
 
-#[derive(Serialize)]
-pub struct MempoolResponse {
-    pub size: u64,
-    pub bytes: u64,
-    pub total_fee: f64,
-    pub min_fee: f64,
-    pub instantsend_locks: u64,
-    pub transactions: Vec<String>,
+/// Fee rate histogram bounds in duffs per byte
+const FEE_RATE_BOUNDS: [f64; 9] = [0.0, 1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0, 100.0];
+/// Transaction size histogram bounds in bytes
+const SIZE_BOUNDS: [f64; 7] = [0.0, 250.0, 500.0, 1000.0, 2000.0, 5000.0, 10_000.0];
+/// Mempool age histogram bounds in seconds
+const AGE_BOUNDS: [f64; 7] = [0.0, 60.0, 300.0, 900.0, 3600.0, 21_600.0, 86_400.0];
+
+#[derive(Deserialize)]
+pub struct MempoolParams {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+    /// `time`, `fee`, `fee_rate`, `size` or `ancestors`
+    pub sort: Option<String>,
+    /// `asc` or `desc`
+    pub order: Option<String>,
+}
+
+/// Count entries into buckets starting at `bounds`, by the value `key` returns
+fn histogram(
+    bounds: &[f64],
+    entries: &[MempoolEntry],
+    key: impl Fn(&MempoolEntry) -> f64,
+) -> Vec<MempoolBucket> {
+    let mut buckets: Vec<MempoolBucket> = bounds
+        .iter()
+        .enumerate()
+        .map(|(i, &min)| MempoolBucket {
+            min,
+            max: bounds.get(i + 1).copied(),
+            count: 0,
+            bytes: 0,
+            fees: 0.0,
+        })
+        .collect();
+    for entry in entries {
+        let value = key(entry);
+        let i = bounds.iter().rposition(|&b| value >= b).unwrap_or(0);
+        buckets[i].count += 1;
+        buckets[i].bytes += entry.size;
+        buckets[i].fees += entry.fee;
+    }
+    buckets
 }
 
 pub async fn get_mempool(
     State(state): State<AppState>,
+    Query(params): Query<MempoolParams>,
 ) -> Result<Json<MempoolResponse>, AppError> {
-    let (mempool_res, txids_res) = tokio::join!(
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params
+        .limit
+        .unwrap_or(50)
+        .min(state.config.limits.mempool_page)
+        .max(1);
+    let sort = params.sort.as_deref().unwrap_or("time");
+    let descending = match params.order.as_deref().unwrap_or("desc") {
+        "asc" => false,
+        "desc" => true,
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unknown order '{}'; use asc or desc",
+                other
+            )))
+        }
+    };
+    let key: fn(&MempoolEntry, &MempoolEntry) -> Ordering = match sort {
+        "time" => |a, b| a.time.cmp(&b.time),
+        "fee" => |a, b| a.fee.total_cmp(&b.fee),
+        "fee_rate" => |a, b| a.fee_rate.total_cmp(&b.fee_rate),
+        "size" => |a, b| a.size.cmp(&b.size),
+        "ancestors" => |a, b| a.ancestors.cmp(&b.ancestors),
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unknown sort '{}'; use time, fee, fee_rate, size or ancestors",
+                other
+            )))
+        }
+    };
+
+    let snapshot = mempool_snapshot(&state).await?;
+    let mut entries: Vec<&MempoolEntry> = snapshot.entries.iter().collect();
+    entries.sort_by(|a, b| {
+        let ordering = key(a, b).then_with(|| a.txid.cmp(&b.txid));
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    Ok(Json(MempoolResponse {
+        size: snapshot.size,
+        bytes: snapshot.bytes,
+        total_fee: snapshot.total_fee,
+        min_fee: snapshot.min_fee,
+        instantsend_locks: snapshot.instantsend_locks,
+        locked: snapshot.locked,
+        unlocked: snapshot.unlocked,
+        fee_histogram: snapshot.fee_histogram.clone(),
+        size_histogram: snapshot.size_histogram.clone(),
+        age_histogram: snapshot.age_histogram.clone(),
+        tx_types: snapshot.tx_types.clone(),
+        page,
+        pages: (entries.len() as u32).div_ceil(limit),
+        entries: entries
+            .into_iter()
+            .skip((page as usize - 1).saturating_mul(limit as usize))
+            .take(limit as usize)
+            .cloned()
+            .collect(),
+    }))
+}
+
+/// Mempool summary with every entry, unsorted. Decoding new transactions is costly
+/// with a large mempool, so the result is shared for `cache.mempool.ttl_secs`.
+async fn mempool_snapshot(state: &AppState) -> Result<Arc<MempoolResponse>, AppError> {
+    if let Some(cached) = state.cache.mempool.get("mempool").await {
+        return Ok(cached);
+    }
+
+    let (mempool_res, pool_res) = tokio::join!(
         state.rpc.get_mempool_info(),
-        state.rpc.get_raw_mempool_txids(),
+        state.rpc.get_raw_mempool_verbose(),
     );
-
     let mempool = mempool_res?;
-    let txids = txids_res?;
+    let pool = pool_res?;
 
-    Ok(Json(MempoolResponse {
+    let txids: Vec<String> = pool.keys().cloned().collect();
+    let decoded = crate::mempool::transactions(state, &txids).await?;
+
+    // Transactions mined or evicted since getrawmempool are left out
+    let entries: Vec<MempoolEntry> = pool
+        .into_iter()
+        .filter_map(|(txid, entry)| {
+            let tx = decoded.get(&txid)?;
+            let fee = entry.base_fee();
+            Some(MempoolEntry {
+                tx_type: tx.tx_type,
+                tx_type_label: tx_type_label(tx.tx_type),
+                fee,
+                fee_rate: if entry.size > 0 {
+                    fee * 1e8 / entry.size as f64
+                } else {
+                    0.0
+                },
+                size: entry.size,
+                time: entry.time,
+                height: entry.height,
+                ancestors: entry.ancestorcount,
+                descendants: entry.descendantcount,
+                depends: entry.depends,
+                instantlock: entry.instantlock,
+                total_output: tx.vout.iter().map(|o| o.value).sum(),
+                txid,
+            })
+        })
+        .collect();
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let locked = entries.iter().filter(|e| e.instantlock).count();
+
+    let snapshot = Arc::new(MempoolResponse {
         size: mempool.size,
         bytes: mempool.bytes,
         total_fee: mempool.total_fee,
         min_fee: mempool.mempoolminfee,
         instantsend_locks: mempool.instantsendlocks,
-        transactions: txids,
-    }))
+        locked,
+        unlocked: entries.len() - locked,
+        fee_histogram: histogram(&FEE_RATE_BOUNDS, &entries, |e| e.fee_rate),
+        size_histogram: histogram(&SIZE_BOUNDS, &entries, |e| e.size as f64),
+        age_histogram: histogram(&AGE_BOUNDS, &entries, |e| {
+            now.saturating_sub(e.time) as f64
+        }),
+        tx_types: TxTypeCount::count(
+            entries
+                .iter()
+                .map(|e| (e.tx_type, e.tx_type_label.as_str())),
+        ),
+        page: 1,
+        pages: 1,
+        entries,
+    });
+    state
+        .cache
+        .mempool
+        .insert("mempool".to_string(), snapshot.clone())
+        .await;
+    Ok(snapshot)
+}
+
+/// Networks served by this instance; the first is also served at the root paths
+pub async fn list_networks(
+    State(networks): State<Arc<Vec<AppState>>>,
+) -> Json<NetworkListResponse> {
+    let heights = join_all(networks.iter().map(|n| n.rpc.get_block_count())).await;
+
+    let summaries = networks
+        .iter()
+        .zip(heights)
+        .enumerate()
+        .map(|(i, (state, height))| {
+            let name = state.config.profile.clone();
+            NetworkSummary {
+                api: format!("/{}/api", name),
+                insight_api: format!("/{}/insight-api", name),
+                default: i == 0,
+                block_height: height.ok(),
+                name,
+            }
+        })
+        .collect();
+
+    Json(NetworkListResponse {
+        default: networks[0].config.profile.clone(),
+        networks: summaries,
+    })
 }
@@ -0,0 +1,64 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::Deserialize;
+
+use crate::masternodes::protx_list;
+use crate::models::platform::{PlatformNode, PlatformNodesResponse};
+use crate::platform;
+use crate::AppError;
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub struct PlatformNodesParams {
+    /// Check whether each node's Platform HTTP port accepts connections
+    pub probe: Option<bool>,
+}
+
+pub async fn list_nodes(
+    State(state): State<AppState>,
+    Query(params): Query<PlatformNodesParams>,
+) -> Result<Json<PlatformNodesResponse>, AppError> {
+    let probe = params.probe.unwrap_or(false);
+    if probe && !state.config.platform.probe {
+        return Err(AppError::BadRequest(
+            "Platform probes are disabled on this server".into(),
+        ));
+    }
+
+    let (height, list) = protx_list(&state).await?;
+    let mut nodes: Vec<PlatformNode> = list
+        .iter()
+        .filter(|mn| mn.mn_type == "Evo")
+        .map(PlatformNode::from_protx)
+        .collect();
+    nodes.sort_by(|a, b| a.pro_tx_hash.cmp(&b.pro_tx_hash));
+
+    if probe {
+        let addresses: Vec<String> = nodes
+            .iter()
+            .filter_map(|n| n.platform_http_address.clone())
+            .collect();
+        let mut probes = platform::probe(&state, &addresses).await;
+        for node in &mut nodes {
+            if let Some(ref address) = node.platform_http_address {
+                node.probe = probes.remove(address);
+            }
+        }
+    }
+
+    Ok(Json(PlatformNodesResponse {
+        height,
+        total: nodes.len(),
+        enabled: nodes.iter().filter(|n| n.status == "ENABLED").count(),
+        platform_banned: nodes
+            .iter()
+            .filter(|n| n.is_platform_banned == Some(true))
+            .count(),
+        probed: nodes.iter().filter(|n| n.probe.is_some()).count(),
+        reachable: nodes
+            .iter()
+            .filter(|n| n.probe.as_ref().is_some_and(|p| p.reachable))
+            .count(),
+        nodes,
+    }))
+}
@@ -0,0 +1,107 @@
+use axum::extract::State;
+use axum::Json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::models::pool::{PoolShare, PoolStatsResponse, PoolWindow};
+use crate::pools;
+use crate::AppError;
+use crate::AppState;
+
+/// Windows reported by `/api/pools`, longest last
+const WINDOWS: [(&str, u64); 2] = [("24h", 86_400), ("7d", 7 * 86_400)];
+
+/// Headers fetched per batch while walking back through the longest window
+const WALK_BATCH: u64 = 200;
+
+pub async fn get_pools(State(state): State<AppState>) -> Result<Json<PoolStatsResponse>, AppError> {
+    if let Some(cached) = state.cache.pool_stats.get("pools").await {
+        return Ok(Json((*cached).clone()));
+    }
+
+    let tip = state.rpc.get_block_count().await?;
+    let tip_header = state
+        .rpc
+        .get_block_header(&state.rpc.get_block_hash(tip).await?)
+        .await?;
+    let longest = WINDOWS[WINDOWS.len() - 1].1;
+    let oldest = tip_header.time.saturating_sub(longest);
+
+    // (hash, time) of every block in the longest window, newest first
+    let mut blocks: Vec<(String, u64)> = Vec::new();
+    let mut h = tip;
+    'walk: loop {
+        let low = h.saturating_sub(WALK_BATCH - 1);
+        let heights: Vec<u64> = (low..=h).rev().collect();
+        let hashes = state.rpc.get_block_hashes(&heights).await?;
+        for header in state.rpc.get_block_headers(&hashes).await? {
+            if header.time < oldest {
+                break 'walk;
+            }
+            blocks.push((header.hash, header.time));
+        }
+        if low == 0 {
+            break;
+        }
+        h = low - 1;
+    }
+
+    let hashes: Vec<String> = blocks.iter().map(|(hash, _)| hash.clone()).collect();
+    let miners = pools::block_miners(&state, &hashes).await?;
+
+    let mut windows = Vec::with_capacity(WINDOWS.len());
+    for (name, secs) in WINDOWS {
+        let since = tip_header.time.saturating_sub(secs);
+        let mut counts: HashMap<Option<String>, (u32, Option<String>)> = HashMap::new();
+        let mut total = 0u32;
+        for ((_, time), miner) in blocks.iter().zip(&miners) {
+            if *time < since {
+                continue;
+            }
+            total += 1;
+            let entry = counts
+                .entry(miner.pool.clone())
+                .or_insert((0, miner.link.clone()));
+            entry.0 += 1;
+        }
+
+        let network_hashrate = if total > 0 {
+            state.rpc.get_network_hash_ps(total as u64).await?
+        } else {
+            0.0
+        };
+
+        let mut pools: Vec<PoolShare> = counts
+            .into_iter()
+            .map(|(pool, (blocks, link))| {
+                let share = blocks as f64 / total as f64;
+                PoolShare {
+                    name: pool.unwrap_or_else(|| "Unknown".to_string()),
+                    link,
+                    blocks,
+                    share: share * 100.0,
+                    hashrate: share * network_hashrate,
+                }
+            })
+            .collect();
+        pools.sort_by(|a, b| b.blocks.cmp(&a.blocks).then_with(|| a.name.cmp(&b.name)));
+
+        windows.push(PoolWindow {
+            window: name.to_string(),
+            blocks: total,
+            network_hashrate,
+            pools,
+        });
+    }
+
+    let response = Arc::new(PoolStatsResponse {
+        height: tip,
+        windows,
+    });
+    state
+        .cache
+        .pool_stats
+        .insert("pools".to_string(), response.clone())
+        .await;
+    Ok(Json((*response).clone()))
+}
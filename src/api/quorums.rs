@@ -0,0 +1,129 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::models::quorum::{QuorumDetail, QuorumListResponse, QuorumTypeGroup};
+use crate::rpc::types::RpcQuorumInfo;
+use crate::AppError;
+use crate::AppState;
+
+/// LLMQ types by the name `quorum list` reports them under
+const LLMQ_TYPES: [(u32, &str); 14] = [
+    (1, "llmq_50_60"),
+    (2, "llmq_400_60"),
+    (3, "llmq_400_85"),
+    (4, "llmq_100_67"),
+    (5, "llmq_60_75"),
+    (6, "llmq_25_67"),
+    (100, "llmq_test"),
+    (101, "llmq_devnet"),
+    (102, "llmq_test_v17"),
+    (103, "llmq_test_dip0024"),
+    (104, "llmq_test_instantsend"),
+    (105, "llmq_devnet_dip0024"),
+    (106, "llmq_test_platform"),
+    (107, "llmq_devnet_platform"),
+];
+
+/// LLMQ type from its number or name
+fn llmq_type(name_or_id: &str) -> Option<u32> {
+    LLMQ_TYPES
+        .iter()
+        .find(|(id, name)| *name == name_or_id || id.to_string() == name_or_id)
+        .map(|(id, _)| *id)
+}
+
+/// Active quorums with their members, in `quorum list` order per type.
+/// Types this build doesn't know are left out.
+pub(crate) async fn active_quorums(state: &AppState) -> Result<Arc<Vec<QuorumDetail>>, AppError> {
+    if let Some(cached) = state.cache.quorums.get("active").await {
+        return Ok(cached);
+    }
+
+    let list = state.rpc.get_quorum_list().await?;
+    let mut requests: Vec<(u32, String)> = Vec::new();
+    for (id, name) in LLMQ_TYPES {
+        for hash in list.get(name).into_iter().flatten() {
+            requests.push((id, hash.clone()));
+        }
+    }
+
+    // A quorum can rotate out between `quorum list` and `quorum info`; skip it
+    let infos: Vec<(u32, RpcQuorumInfo)> = requests
+        .iter()
+        .zip(state.rpc.get_quorum_infos(&requests).await?)
+        .filter_map(|((id, _), info)| Some((*id, info.ok()?)))
+        .collect();
+    let mined: Vec<String> = infos.iter().map(|(_, q)| q.mined_block.clone()).collect();
+    let heights: HashMap<String, u64> = state
+        .rpc
+        .get_block_headers(&mined)
+        .await?
+        .into_iter()
+        .map(|h| (h.hash, h.height))
+        .collect();
+
+    let quorums: Vec<QuorumDetail> = infos
+        .iter()
+        .map(|(id, info)| {
+            QuorumDetail::from_rpc(info, *id, heights.get(&info.mined_block).copied(), true)
+        })
+        .collect();
+    let quorums = Arc::new(quorums);
+    state
+        .cache
+        .quorums
+        .insert("active".to_string(), quorums.clone())
+        .await;
+    Ok(quorums)
+}
+
+pub async fn list_quorums(
+    State(state): State<AppState>,
+) -> Result<Json<QuorumListResponse>, AppError> {
+    let quorums = active_quorums(&state).await?;
+
+    let mut types: Vec<QuorumTypeGroup> = Vec::new();
+    for quorum in quorums.iter() {
+        match types.iter_mut().find(|t| t.llmq_type == quorum.llmq_type) {
+            Some(group) => group.quorums.push(quorum.summary()),
+            None => types.push(QuorumTypeGroup {
+                quorum_type: quorum.quorum_type.clone(),
+                llmq_type: quorum.llmq_type,
+                quorums: vec![quorum.summary()],
+            }),
+        }
+    }
+
+    Ok(Json(QuorumListResponse {
+        total: quorums.len(),
+        types,
+    }))
+}
+
+pub async fn get_quorum(
+    State(state): State<AppState>,
+    Path((quorum_type, hash)): Path<(String, String)>,
+) -> Result<Json<QuorumDetail>, AppError> {
+    let id = llmq_type(&quorum_type)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown quorum type '{}'", quorum_type)))?;
+
+    let active = active_quorums(&state).await?;
+    if let Some(quorum) = active
+        .iter()
+        .find(|q| q.llmq_type == id && q.quorum_hash == hash)
+    {
+        return Ok(Json(quorum.clone()));
+    }
+
+    // Quorums that have rotated out are still known to the node
+    let info = state.rpc.get_quorum_info(id, &hash).await?;
+    let mined_height = state
+        .rpc
+        .get_block_header(&info.mined_block)
+        .await
+        .ok()
+        .map(|h| h.height);
+    Ok(Json(QuorumDetail::from_rpc(&info, id, mined_height, false)))
+}
@@ -0,0 +1,50 @@
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Fixed one-minute window request counter per client IP
+pub struct RateLimiter {
+    per_minute: u32,
+    window: Mutex<(Instant, HashMap<IpAddr, u32>)>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            window: Mutex::new((Instant::now(), HashMap::new())),
+        }
+    }
+
+    fn allow(&self, ip: IpAddr) -> bool {
+        let mut window = self.window.lock().unwrap();
+        if window.0.elapsed() >= WINDOW {
+            *window = (Instant::now(), HashMap::new());
+        }
+        let count = window.1.entry(ip).or_insert(0);
+        *count += 1;
+        *count <= self.per_minute
+    }
+}
+
+pub async fn limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !limiter.allow(addr.ip()) {
+        let body = json!({ "error": "Rate limit exceeded, try again in a minute" });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+    }
+    next.run(request).await
+}
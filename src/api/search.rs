@@ -67,7 +67,7 @@ pub async fn search(
 
     // 3. Starts with y or X (Dash address prefixes, testnet uses y)
     if q.starts_with('y') || q.starts_with('X') || q.starts_with('8') || q.starts_with('7') {
-        if let Ok(_balance) = state.rpc.get_address_balance(q).await {
+        if let Ok(_balance) = state.get_address_balance(q).await {
             return Ok(Json(SearchResult {
                 result_type: "address".to_string(),
                 value: q.to_string(),
@@ -0,0 +1,494 @@
+use axum::body::Bytes;
+use axum::extract::ws::rejection::WebSocketUpgradeRejection;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Notify};
+
+use crate::live::LiveEvent;
+use crate::rpc::types::RpcTransaction;
+use crate::rpc::DashRpcClient;
+use crate::AppState;
+
+// Engine.IO v3 (Socket.IO 1.x/2.x, as used by insight-client/bitcore) timings
+const PING_INTERVAL: Duration = Duration::from_secs(25);
+const PING_TIMEOUT: Duration = Duration::from_secs(60);
+/// Packets buffered per session before the oldest are dropped
+const MAX_OUTBOX: usize = 1000;
+/// Open sessions across all clients; handshakes beyond this are refused
+const MAX_SESSIONS: usize = 10_000;
+/// Addresses one session can watch in the `dashd/addresstxid` room
+const MAX_WATCHED_ADDRESSES: usize = 1000;
+
+const INV_ROOM: &str = "inv";
+const ADDRESS_TXID_EVENT: &str = "dashd/addresstxid";
+
+/// Rooms a legacy Insight client has joined
+#[derive(Default)]
+struct Rooms {
+    inv: bool,
+    addresses: HashSet<String>,
+}
+
+struct Session {
+    rooms: Mutex<Rooms>,
+    /// Encoded Engine.IO packets waiting for the client
+    outbox: Mutex<VecDeque<String>>,
+    notify: Notify,
+    last_seen: Mutex<Instant>,
+    upgraded: AtomicBool,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            rooms: Mutex::new(Rooms::default()),
+            outbox: Mutex::new(VecDeque::new()),
+            notify: Notify::new(),
+            last_seen: Mutex::new(Instant::now()),
+            upgraded: AtomicBool::new(false),
+        }
+    }
+
+    fn push(&self, packet: String) {
+        let mut outbox = self.outbox.lock().unwrap();
+        if outbox.len() >= MAX_OUTBOX {
+            outbox.pop_front();
+        }
+        outbox.push_back(packet);
+        drop(outbox);
+        self.notify.notify_one();
+    }
+
+    fn emit(&self, event: &str, data: Value) {
+        self.push(format!("42{}", json!([event, data])));
+    }
+
+    fn drain(&self) -> Vec<String> {
+        self.outbox.lock().unwrap().drain(..).collect()
+    }
+
+    fn touch(&self) {
+        *self.last_seen.lock().unwrap() = Instant::now();
+    }
+}
+
+/// Socket.IO sessions fed from the live updater broadcast channel
+#[derive(Default)]
+pub struct SocketIoHub {
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+}
+
+impl SocketIoHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn open_session(&self) -> (String, Arc<Session>) {
+        // The sid is the only credential for polling a session, so it must be unguessable
+        let sid: String = rand::random::<[u8; 16]>()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let session = Arc::new(Session::new());
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(sid.clone(), session.clone());
+        (sid, session)
+    }
+
+    fn is_full(&self) -> bool {
+        self.sessions.lock().unwrap().len() >= MAX_SESSIONS
+    }
+
+    fn session(&self, sid: &str) -> Option<Arc<Session>> {
+        self.sessions.lock().unwrap().get(sid).cloned()
+    }
+
+    fn close_session(&self, sid: &str) {
+        self.sessions.lock().unwrap().remove(sid);
+    }
+
+    fn snapshot(&self) -> Vec<Arc<Session>> {
+        self.sessions.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Translate live events into Insight room events until the channel closes
+    pub async fn run(
+        self: Arc<Self>,
+        rpc: Arc<DashRpcClient>,
+        mut rx: broadcast::Receiver<LiveEvent>,
+    ) {
+        let mut sweep = tokio::time::interval(PING_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(event) => self.dispatch(&rpc, event).await,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("Socket.IO hub lagged by {} messages", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+                _ = sweep.tick() => {
+                    // Drop sessions whose client stopped polling or pinging
+                    let expiry = PING_INTERVAL + PING_TIMEOUT;
+                    self.sessions
+                        .lock()
+                        .unwrap()
+                        .retain(|_, s| s.last_seen.lock().unwrap().elapsed() < expiry);
+                }
+            }
+        }
+    }
+
+    async fn dispatch(&self, rpc: &DashRpcClient, event: LiveEvent) {
+        let sessions = self.snapshot();
+        if sessions.is_empty() {
+            return;
+        }
+        let in_inv = |s: &Session| s.rooms.lock().unwrap().inv;
+
+        match event {
+            LiveEvent::NewBlock { hash, .. } => {
+                for session in sessions.iter().filter(|s| in_inv(s)) {
+                    session.emit("block", json!(hash));
+                }
+            }
+            LiveEvent::NewTransaction {
+                txid, addresses, ..
+            } => {
+                for session in &sessions {
+                    let rooms = session.rooms.lock().unwrap();
+                    let watched: Vec<&String> = addresses
+                        .iter()
+                        .filter(|a| rooms.addresses.contains(*a))
+                        .collect();
+                    drop(rooms);
+                    for address in watched {
+                        session.emit(
+                            ADDRESS_TXID_EVENT,
+                            json!({ "address": address, "txid": txid }),
+                        );
+                    }
+                }
+
+                if sessions.iter().any(|s| in_inv(s)) {
+                    if let Ok(tx) = rpc.get_raw_transaction(&txid).await {
+                        let data = insight_tx_message(&tx, tx.instantlock);
+                        for session in sessions.iter().filter(|s| in_inv(s)) {
+                            session.emit("tx", data.clone());
+                        }
+                    }
+                }
+            }
+            LiveEvent::InstantLock { txid } if sessions.iter().any(|s| in_inv(s)) => {
+                let data = match rpc.get_raw_transaction(&txid).await {
+                    Ok(tx) => insight_tx_message(&tx, true),
+                    Err(_) => json!({ "txid": txid, "txlock": true }),
+                };
+                for session in sessions.iter().filter(|s| in_inv(s)) {
+                    session.emit("txlock", data.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Insight's `tx` room payload
+fn insight_tx_message(tx: &RpcTransaction, txlock: bool) -> Value {
+    let vout: Vec<Value> = tx
+        .vout
+        .iter()
+        .filter_map(|o| {
+            let addr = o.script_pub_key.address.as_ref()?;
+            Some(json!({ addr.as_str(): o.value_sat }))
+        })
+        .collect();
+    json!({
+        "txid": tx.txid,
+        "valueOut": tx.vout.iter().map(|o| o.value).sum::<f64>(),
+        "vout": vout,
+        "isRBF": false,
+        "txlock": txlock,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct EngineParams {
+    #[serde(rename = "EIO")]
+    pub eio: Option<String>,
+    pub transport: Option<String>,
+    pub sid: Option<String>,
+}
+
+fn open_packet(sid: &str) -> String {
+    let handshake = json!({
+        "sid": sid,
+        "upgrades": ["websocket"],
+        "pingInterval": PING_INTERVAL.as_millis() as u64,
+        "pingTimeout": PING_TIMEOUT.as_millis() as u64,
+    });
+    format!("0{}", handshake)
+}
+
+fn engine_error(code: u32, message: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        axum::Json(json!({ "code": code, "message": message })),
+    )
+        .into_response()
+}
+
+/// Engine.IO v3 polling payload: `<length>:<packet>` repeated
+fn encode_payload(packets: &[String]) -> String {
+    packets
+        .iter()
+        .map(|p| format!("{}:{}", p.encode_utf16().count(), p))
+        .collect()
+}
+
+fn decode_payload(body: &str) -> Vec<String> {
+    let mut packets = Vec::new();
+    let mut rest = body;
+    while let Some((len, tail)) = rest.split_once(':') {
+        let Ok(len) = len.parse::<usize>() else {
+            break;
+        };
+        // Lengths count UTF-16 code units, as in the JS implementation
+        let mut units = 0;
+        let end = tail
+            .char_indices()
+            .find(|(_, c)| {
+                let done = units >= len;
+                units += c.len_utf16();
+                done
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(tail.len());
+        packets.push(tail[..end].to_string());
+        rest = &tail[end..];
+    }
+    packets
+}
+
+fn polling_response(packets: &[String]) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=UTF-8")],
+        encode_payload(packets),
+    )
+        .into_response()
+}
+
+/// GET /socket.io/ - handshake, long-poll or WebSocket upgrade
+pub async fn get_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EngineParams>,
+    ws: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+    if params.eio.as_deref() != Some("3") {
+        return engine_error(5, "Unsupported protocol version");
+    }
+    let hub = state.socketio.clone();
+    if params.sid.is_none() && hub.is_full() {
+        return engine_error(3, "Too many sessions");
+    }
+
+    match (params.transport.as_deref(), ws) {
+        (Some("websocket"), Ok(ws)) => {
+            let existing = match params.sid {
+                Some(ref sid) => match hub.session(sid) {
+                    Some(session) => Some((sid.clone(), session)),
+                    None => return engine_error(1, "Session ID unknown"),
+                },
+                None => None,
+            };
+            ws.on_upgrade(move |socket| handle_websocket(socket, hub, existing))
+        }
+        (Some("polling"), _) => match params.sid {
+            None => {
+                let (sid, session) = hub.open_session();
+                session.push("40".to_string());
+                let mut packets = vec![open_packet(&sid)];
+                packets.extend(session.drain());
+                polling_response(&packets)
+            }
+            Some(sid) => {
+                let Some(session) = hub.session(&sid) else {
+                    return engine_error(1, "Session ID unknown");
+                };
+                session.touch();
+                let mut packets = session.drain();
+                if packets.is_empty() && !session.upgraded.load(Ordering::Relaxed) {
+                    let _ = tokio::time::timeout(PING_INTERVAL, session.notify.notified()).await;
+                    packets = session.drain();
+                }
+                if packets.is_empty() {
+                    packets.push("6".to_string());
+                }
+                polling_response(&packets)
+            }
+        },
+        _ => engine_error(0, "Transport unknown"),
+    }
+}
+
+/// POST /socket.io/ - packets sent by a polling client
+pub async fn post_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EngineParams>,
+    body: Bytes,
+) -> Response {
+    let Some(sid) = params.sid else {
+        return engine_error(1, "Session ID unknown");
+    };
+    let Some(session) = state.socketio.session(&sid) else {
+        return engine_error(1, "Session ID unknown");
+    };
+    session.touch();
+
+    let body = String::from_utf8_lossy(&body);
+    for packet in decode_payload(&body) {
+        if !handle_packet(&session, &packet) {
+            state.socketio.close_session(&sid);
+            break;
+        }
+    }
+
+    ([(header::CONTENT_TYPE, "text/html")], "ok").into_response()
+}
+
+async fn handle_websocket(
+    mut socket: WebSocket,
+    hub: Arc<SocketIoHub>,
+    existing: Option<(String, Arc<Session>)>,
+) {
+    let (sid, session) = match existing {
+        Some(pair) => pair,
+        None => {
+            let (sid, session) = hub.open_session();
+            if socket
+                .send(Message::Text(open_packet(&sid).into()))
+                .await
+                .is_err()
+            {
+                hub.close_session(&sid);
+                return;
+            }
+            session.push("40".to_string());
+            session.upgraded.store(true, Ordering::Relaxed);
+            (sid, session)
+        }
+    };
+
+    loop {
+        // Packets keep flowing over polling until the client completes the upgrade
+        let upgraded = session.upgraded.load(Ordering::Relaxed);
+        if upgraded {
+            for packet in session.drain() {
+                if socket.send(Message::Text(packet.into())).await.is_err() {
+                    hub.close_session(&sid);
+                    return;
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = session.notify.notified(), if upgraded => {}
+            msg = socket.recv() => {
+                session.touch();
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        if text.as_str() == "2probe" {
+                            let _ = socket.send(Message::Text("3probe".into())).await;
+                        } else if text.as_str() == "5" {
+                            // Upgrade complete: release a pending long-poll
+                            session.upgraded.store(true, Ordering::Relaxed);
+                            session.notify.notify_waiters();
+                        } else if !handle_packet(&session, &text) {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    hub.close_session(&sid);
+}
+
+/// Handle one Engine.IO packet from the client. Returns false when the client closed.
+fn handle_packet(session: &Session, packet: &str) -> bool {
+    let (kind, data) = split_type(packet);
+    match kind {
+        // ping -> pong
+        "2" => session.push(format!("3{}", data)),
+        // Socket.IO packet
+        "4" => handle_socketio_packet(session, data),
+        "1" => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Split a packet into its one-character type and the rest
+fn split_type(packet: &str) -> (&str, &str) {
+    packet.split_at(packet.chars().next().map_or(0, char::len_utf8))
+}
+
+fn handle_socketio_packet(session: &Session, packet: &str) {
+    let (kind, data) = split_type(packet);
+    match kind {
+        // CONNECT to a namespace; only the default one exists
+        "0" if data.is_empty() || data == "/" => session.push("40".to_string()),
+        // EVENT, optionally followed by an ack id before the JSON array
+        "2" => {
+            let json_start = data.find('[').unwrap_or(data.len());
+            let Ok(Value::Array(args)) = serde_json::from_str::<Value>(&data[json_start..]) else {
+                return;
+            };
+            let name = args.first().and_then(|v| v.as_str()).unwrap_or_default();
+            let room = args.get(1).and_then(|v| v.as_str()).unwrap_or_default();
+            let addresses: Vec<String> = args
+                .get(2)
+                .and_then(|v| v.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut rooms = session.rooms.lock().unwrap();
+            match (name, room) {
+                ("subscribe", INV_ROOM) => rooms.inv = true,
+                ("unsubscribe", INV_ROOM) => rooms.inv = false,
+                ("subscribe", ADDRESS_TXID_EVENT) => {
+                    let room_left = MAX_WATCHED_ADDRESSES.saturating_sub(rooms.addresses.len());
+                    rooms.addresses.extend(addresses.into_iter().take(room_left));
+                }
+                ("unsubscribe", ADDRESS_TXID_EVENT) => {
+                    if addresses.is_empty() {
+                        rooms.addresses.clear();
+                    }
+                    for addr in &addresses {
+                        rooms.addresses.remove(addr);
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
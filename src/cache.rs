@@ -1,14 +1,24 @@
 use moka::future::Cache;
+use std::sync::Arc;
 use std::time::Duration;
 
-use crate::models::block::{BlockDetail, BlockSummary};
-use crate::models::masternode::MasternodeSummary;
-use crate::models::network::StatusResponse;
+use crate::config::{CacheConfig, CacheSize};
+use crate::models::block::{BlockDetail, BlockStats, BlockSummary};
+use crate::models::chart::ChartResponse;
+use crate::models::fee::FeesResponse;
+use crate::models::masternode::{
+    MasternodeHistoryResponse, MasternodePaymentScan, MasternodeStatsResponse, MasternodeSummary,
+};
+use crate::models::network::{MempoolResponse, StatusResponse};
+use crate::models::platform::PlatformProbe;
+use crate::models::pool::{Miner, PoolStatsResponse};
+use crate::models::quorum::QuorumDetail;
 use crate::models::transaction::TransactionDetail;
+use crate::rpc::types::{RpcAddressMempoolEntry, RpcMasternodePayment, RpcProtx, RpcTransaction};
 
 pub struct AppCache {
     /// Confirmed blocks by hash
-    pub blocks: Cache<String, BlockDetail>,
+    pub blocks: Cache<String, Arc<BlockDetail>>,
     /// Block hash by height
     pub block_hash_by_height: Cache<u64, String>,
     /// Confirmed transactions by txid
@@ -19,35 +29,73 @@ pub struct AppCache {
     pub status: Cache<String, StatusResponse>,
     /// Masternode list
     pub masternode_list: Cache<String, Vec<MasternodeSummary>>,
+    /// Decoded mempool transactions, for `/api/mempool` and the address mempool scan
+    pub mempool_txs: Cache<String, Arc<RpcTransaction>>,
+    /// Address deltas of the whole mempool from the last scan
+    pub mempool_deltas: Cache<String, Arc<Vec<RpcAddressMempoolEntry>>>,
+    /// `/api/mempool` summary and unsorted entries
+    pub mempool: Cache<String, Arc<MempoolResponse>>,
+    /// Chart series by metric and query
+    pub charts: Cache<String, Arc<ChartResponse>>,
+    /// Identified miner by block hash
+    pub block_miners: Cache<String, Miner>,
+    /// `getblockstats` totals by block hash
+    pub block_stats: Cache<String, BlockStats>,
+    /// Pool hashrate shares
+    pub pool_stats: Cache<String, Arc<PoolStatsResponse>>,
+    /// Fee estimates by requested targets
+    pub fees: Cache<String, Arc<FeesResponse>>,
+    /// Active quorums with their members
+    pub quorums: Cache<String, Arc<Vec<QuorumDetail>>>,
+    /// Masternode timelines by proTxHash
+    pub masternode_history: Cache<String, Arc<MasternodeHistoryResponse>>,
+    /// Full `protx list` by chain height
+    pub protx_list: Cache<u64, Arc<Vec<RpcProtx>>>,
+    /// Masternode payments by proTxHash and the height they were scanned below
+    pub masternode_payments: Cache<String, Arc<MasternodePaymentScan>>,
+    /// Masternodes paid by each block, by block hash
+    pub block_payees: Cache<String, Arc<Vec<RpcMasternodePayment>>>,
+    /// Masternode breakdowns for `/api/masternodes/stats`
+    pub masternode_stats: Cache<String, Arc<MasternodeStatsResponse>>,
+    /// Platform HTTP port probes by address
+    pub platform_probes: Cache<String, PlatformProbe>,
 }
 
 impl AppCache {
-    pub fn new() -> Self {
+    pub fn new(config: &CacheConfig) -> Self {
         Self {
-            blocks: Cache::builder()
-                .max_capacity(1000)
-                .time_to_live(Duration::from_secs(3600))
-                .build(),
-            block_hash_by_height: Cache::builder()
-                .max_capacity(10000)
-                .time_to_live(Duration::from_secs(3600))
-                .build(),
-            transactions: Cache::builder()
-                .max_capacity(5000)
-                .time_to_live(Duration::from_secs(3600))
-                .build(),
-            latest_blocks: Cache::builder()
-                .max_capacity(10)
-                .time_to_live(Duration::from_secs(10))
-                .build(),
-            status: Cache::builder()
-                .max_capacity(5)
-                .time_to_live(Duration::from_secs(5))
-                .build(),
-            masternode_list: Cache::builder()
-                .max_capacity(10)
-                .time_to_live(Duration::from_secs(120))
-                .build(),
+            blocks: build(config.blocks),
+            block_hash_by_height: build(config.block_hash_by_height),
+            transactions: build(config.transactions),
+            latest_blocks: build(config.latest_blocks),
+            status: build(config.status),
+            masternode_list: build(config.masternode_list),
+            mempool_txs: build(config.mempool_txs),
+            mempool_deltas: build(config.mempool_deltas),
+            mempool: build(config.mempool),
+            charts: build(config.charts),
+            block_miners: build(config.block_miners),
+            block_stats: build(config.block_stats),
+            pool_stats: build(config.pool_stats),
+            fees: build(config.fees),
+            quorums: build(config.quorums),
+            masternode_history: build(config.masternode_history),
+            protx_list: build(config.protx_list),
+            masternode_payments: build(config.masternode_payments),
+            block_payees: build(config.block_payees),
+            masternode_stats: build(config.masternode_stats),
+            platform_probes: build(config.platform_probes),
         }
     }
 }
+
+fn build<K, V>(size: CacheSize) -> Cache<K, V>
+where
+    K: std::hash::Hash + Eq + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    Cache::builder()
+        .max_capacity(size.capacity)
+        .time_to_live(Duration::from_secs(size.ttl_secs))
+        .build()
+}
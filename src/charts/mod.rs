@@ -0,0 +1,232 @@
+mod store;
+mod sync;
+
+use chrono::{Datelike, TimeZone, Utc};
+use std::sync::Arc;
+
+pub use store::ChartStore;
+pub use sync::ChartSyncer;
+
+use crate::models::chart::ChartPoint;
+use crate::AppError;
+
+/// Seconds of history read before `from` so sampled metrics have a starting value
+const SAMPLE_LOOKBACK: u64 = 6 * 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    TxCount,
+    Fees,
+    MedianFeeRate,
+    BlockSize,
+    Difficulty,
+    MasternodeCount,
+    CreditPoolBalance,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 7] = [
+        Metric::TxCount,
+        Metric::Fees,
+        Metric::MedianFeeRate,
+        Metric::BlockSize,
+        Metric::Difficulty,
+        Metric::MasternodeCount,
+        Metric::CreditPoolBalance,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::TxCount => "tx_count",
+            Metric::Fees => "fees",
+            Metric::MedianFeeRate => "median_fee_rate",
+            Metric::BlockSize => "block_size",
+            Metric::Difficulty => "difficulty",
+            Metric::MasternodeCount => "masternode_count",
+            Metric::CreditPoolBalance => "credit_pool_balance",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Metric::TxCount => "transactions",
+            Metric::Fees | Metric::CreditPoolBalance => "DASH",
+            Metric::MedianFeeRate => "duffs/byte",
+            Metric::BlockSize => "bytes",
+            Metric::Difficulty => "difficulty",
+            Metric::MasternodeCount => "masternodes",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interval {
+    Block,
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl Interval {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "block" => Some(Interval::Block),
+            "hour" => Some(Interval::Hour),
+            "day" => Some(Interval::Day),
+            "week" => Some(Interval::Week),
+            "month" => Some(Interval::Month),
+            _ => None,
+        }
+    }
+
+    /// Start of the bucket `time` falls in; weeks start on Monday, months are calendar months (UTC)
+    fn bucket(self, time: u64) -> u64 {
+        match self {
+            Interval::Block => time,
+            Interval::Hour => time - time % 3600,
+            Interval::Day => time - time % 86_400,
+            // The Unix epoch was a Thursday
+            Interval::Week => time - (time + 3 * 86_400) % (7 * 86_400),
+            Interval::Month => {
+                let date = Utc.timestamp_opt(time as i64, 0).single().unwrap_or_default();
+                Utc.with_ymd_and_hms(date.year(), date.month(), 1, 0, 0, 0)
+                    .single()
+                    .map(|d| d.timestamp() as u64)
+                    .unwrap_or(time)
+            }
+        }
+    }
+}
+
+/// Blocks aggregated into one chart point
+#[derive(Default)]
+struct Bucket {
+    time: u64,
+    height: u64,
+    blocks: u32,
+    txs: u64,
+    fees: i64,
+    fee_rates: Vec<f64>,
+    size: u64,
+    difficulty: f64,
+    credit_pool: i64,
+}
+
+impl Bucket {
+    /// None for sampled metrics without a sample yet
+    fn point(&self, metric: Metric, masternodes: Option<u32>) -> Option<ChartPoint> {
+        let blocks = self.blocks as f64;
+        let value = match metric {
+            Metric::TxCount => self.txs as f64,
+            Metric::Fees => self.fees as f64 / 1e8,
+            Metric::MedianFeeRate => {
+                let mut rates = self.fee_rates.clone();
+                rates.sort_by(|a, b| a.total_cmp(b));
+                rates.get(rates.len() / 2).copied().unwrap_or(0.0)
+            }
+            Metric::BlockSize => self.size as f64 / blocks,
+            Metric::Difficulty => self.difficulty / blocks,
+            Metric::MasternodeCount => masternodes? as f64,
+            Metric::CreditPoolBalance => self.credit_pool as f64 / 1e8,
+        };
+        Some(ChartPoint {
+            time: self.time,
+            height: self.height,
+            blocks: self.blocks,
+            value,
+        })
+    }
+}
+
+/// Persisted per-block stats behind `/api/charts`
+pub struct Charts {
+    store: Arc<ChartStore>,
+}
+
+impl Charts {
+    pub fn open(path: &str) -> Result<Self, AppError> {
+        Ok(Self {
+            store: Arc::new(ChartStore::open(path)?),
+        })
+    }
+
+    pub(crate) async fn blocking<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&ChartStore) -> Result<T, AppError> + Send + 'static,
+    {
+        let store = self.store.clone();
+        tokio::task::spawn_blocking(move || f(&store))
+            .await
+            .map_err(|e| AppError::Internal(format!("Chart task failed: {}", e)))?
+    }
+
+    /// Highest block with stored stats
+    pub async fn height(&self) -> Result<Option<u64>, AppError> {
+        let tip = self.blocking(|store| store.tip()).await?;
+        Ok(tip.map(|(height, _)| height))
+    }
+
+    /// Aggregate stored blocks with a time in `from..=to` into at most `max_points` points
+    pub async fn series(
+        &self,
+        metric: Metric,
+        interval: Interval,
+        from: u64,
+        to: u64,
+        max_points: usize,
+    ) -> Result<Vec<ChartPoint>, AppError> {
+        self.blocking(move |store| {
+            let mut points: Vec<ChartPoint> = Vec::new();
+            let mut current: Option<Bucket> = None;
+            let mut masternodes: Option<u32> = None;
+
+            store.for_each_in_range(from.saturating_sub(SAMPLE_LOOKBACK), to, |height, record| {
+                if points.len() > max_points {
+                    return;
+                }
+                // Records come in time order, so buckets never reopen
+                let start = interval.bucket(record.time);
+                if let Some(done) = current.take_if(|b| b.time != start) {
+                    points.extend(done.point(metric, masternodes));
+                }
+                if record.masternodes.is_some() {
+                    masternodes = record.masternodes;
+                }
+                if record.time < from {
+                    return;
+                }
+
+                let bucket = current.get_or_insert_with(|| Bucket {
+                    time: start,
+                    ..Bucket::default()
+                });
+                bucket.height = bucket.height.max(height);
+                bucket.blocks += 1;
+                bucket.txs += record.txs as u64;
+                bucket.fees += record.total_fee;
+                bucket.fee_rates.push(record.median_fee_rate);
+                bucket.size += record.size;
+                bucket.difficulty += record.difficulty;
+                bucket.credit_pool = record.credit_pool;
+            })?;
+            if let Some(done) = current {
+                points.extend(done.point(metric, masternodes));
+            }
+
+            if points.len() > max_points {
+                return Err(AppError::BadRequest(format!(
+                    "Range has more than {} points; use a larger interval or a shorter range",
+                    max_points
+                )));
+            }
+            Ok(points)
+        })
+        .await
+    }
+}
@@ -0,0 +1,125 @@
+use redb::{Database, ReadableTable, TableDefinition};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::AppError;
+
+/// Per-block stats by height
+const STATS: TableDefinition<u64, &[u8]> = TableDefinition::new("block_stats");
+/// (block time, height) for time range queries
+const BY_TIME: TableDefinition<(u64, u64), ()> = TableDefinition::new("block_stats_by_time");
+
+/// Chart inputs for one block, as computed from `getblockstats` and `getblock`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockStatsRecord {
+    pub hash: String,
+    pub time: u64,
+    pub txs: u32,
+    /// Total fees in duffs
+    pub total_fee: i64,
+    /// Median fee rate in duffs per byte
+    pub median_fee_rate: f64,
+    pub size: u64,
+    pub difficulty: f64,
+    /// Valid masternodes, sampled every few blocks
+    pub masternodes: Option<u32>,
+    /// Platform credit pool in duffs
+    pub credit_pool: i64,
+}
+
+pub struct ChartStore {
+    db: Database,
+}
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_json::to_vec(value).expect("chart record serialization cannot fail")
+}
+
+fn decode<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<T, AppError> {
+    serde_json::from_slice(bytes)
+        .map_err(|e| AppError::Internal(format!("Corrupted chart record: {}", e)))
+}
+
+impl ChartStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AppError> {
+        let db = Database::create(path)?;
+
+        let txn = db.begin_write()?;
+        {
+            txn.open_table(STATS)?;
+            txn.open_table(BY_TIME)?;
+        }
+        txn.commit()?;
+
+        Ok(Self { db })
+    }
+
+    /// Highest stored block as (height, hash)
+    pub fn tip(&self) -> Result<Option<(u64, String)>, AppError> {
+        let txn = self.db.begin_read()?;
+        let stats = txn.open_table(STATS)?;
+        let tip = match stats.last()? {
+            Some((height, bytes)) => {
+                let record: BlockStatsRecord = decode(bytes.value())?;
+                Some((height.value(), record.hash))
+            }
+            None => None,
+        };
+        Ok(tip)
+    }
+
+    pub fn insert(&self, records: &[(u64, BlockStatsRecord)]) -> Result<(), AppError> {
+        let txn = self.db.begin_write()?;
+        {
+            let mut stats = txn.open_table(STATS)?;
+            let mut by_time = txn.open_table(BY_TIME)?;
+            for (height, record) in records {
+                stats.insert(*height, encode(record).as_slice())?;
+                by_time.insert((record.time, *height), ())?;
+            }
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Drop the tip record after a reorg
+    pub fn remove_tip(&self) -> Result<Option<(u64, String)>, AppError> {
+        let txn = self.db.begin_write()?;
+        let removed = {
+            let mut stats = txn.open_table(STATS)?;
+            let mut by_time = txn.open_table(BY_TIME)?;
+            let last = stats.pop_last()?;
+            match last {
+                Some((height, bytes)) => {
+                    let height = height.value();
+                    let record: BlockStatsRecord = decode(bytes.value())?;
+                    by_time.remove((record.time, height))?;
+                    Some((height, record.hash))
+                }
+                None => None,
+            }
+        };
+        txn.commit()?;
+        Ok(removed)
+    }
+
+    /// Visit records with a block time in `from..=to`, in time order
+    pub fn for_each_in_range(
+        &self,
+        from: u64,
+        to: u64,
+        mut f: impl FnMut(u64, &BlockStatsRecord),
+    ) -> Result<(), AppError> {
+        let txn = self.db.begin_read()?;
+        let by_time = txn.open_table(BY_TIME)?;
+        let stats = txn.open_table(STATS)?;
+        for entry in by_time.range((from, 0)..=(to, u64::MAX))? {
+            let (key, _) = entry?;
+            let (_, height) = key.value();
+            if let Some(bytes) = stats.get(height)? {
+                f(height, &decode(bytes.value())?);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::store::BlockStatsRecord;
+use super::Charts;
+use crate::rpc::DashRpcClient;
+use crate::AppError;
+
+/// Blocks fetched and stored together while catching up
+const SYNC_BATCH: u64 = 100;
+/// Masternode count is sampled every this many blocks (about an hour)
+const MASTERNODE_SAMPLE: u64 = 24;
+
+/// Computes chart inputs for every block on the node's active chain and persists them
+pub struct ChartSyncer {
+    rpc: Arc<DashRpcClient>,
+    charts: Arc<Charts>,
+}
+
+impl ChartSyncer {
+    pub fn new(rpc: Arc<DashRpcClient>, charts: Arc<Charts>) -> Self {
+        Self { rpc, charts }
+    }
+
+    pub async fn run(self) {
+        tracing::info!("Chart syncer started");
+
+        loop {
+            match self.sync_step().await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Chart sync failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    }
+
+    /// Store the next batch of blocks. Returns true when more blocks are pending.
+    async fn sync_step(&self) -> Result<bool, AppError> {
+        let node_height = self.rpc.get_block_count().await?;
+        let local_tip = self.charts.blocking(|store| store.tip()).await?;
+
+        let (next_height, tip_hash) = match local_tip {
+            Some((height, hash)) => {
+                if height > node_height || self.rpc.get_block_hash(height).await? != hash {
+                    self.charts.blocking(|store| store.remove_tip()).await?;
+                    return Ok(true);
+                }
+                (height + 1, Some(hash))
+            }
+            None => (0, None),
+        };
+
+        if next_height > node_height {
+            return Ok(false);
+        }
+
+        let last = (next_height + SYNC_BATCH - 1).min(node_height);
+        let heights: Vec<u64> = (next_height..=last).collect();
+        let hashes = self.rpc.get_block_hashes(&heights).await?;
+        let (stats, blocks) = tokio::join!(
+            self.rpc.get_block_stats_batch(&heights),
+            self.rpc.get_blocks(&hashes),
+        );
+        let stats = stats?.into_iter().collect::<Result<Vec<_>, _>>()?;
+        let blocks = blocks?;
+
+        let mut records = Vec::with_capacity(heights.len());
+        let mut prev_hash = tip_hash;
+        for ((height, stats), block) in heights.iter().zip(stats).zip(blocks) {
+            // The chain moved under us mid-batch; the next step rewinds if needed
+            if stats.blockhash != block.hash
+                || (*height > 0 && block.previous_block_hash != prev_hash)
+            {
+                break;
+            }
+
+            let masternodes = if height % MASTERNODE_SAMPLE == 0 || *height == node_height {
+                match self.rpc.get_valid_protx_hashes(*height).await {
+                    Ok(hashes) => Some(hashes.len() as u32),
+                    // Heights before deterministic masternode lists
+                    Err(AppError::Rpc { .. }) => None,
+                    Err(e) => return Err(e),
+                }
+            } else {
+                None
+            };
+
+            prev_hash = Some(block.hash.clone());
+            records.push((
+                *height,
+                BlockStatsRecord {
+                    hash: block.hash,
+                    time: block.time,
+                    txs: stats.txs,
+                    total_fee: stats.totalfee as i64,
+                    median_fee_rate: stats.feerate_percentiles.get(2).copied().unwrap_or(0.0),
+                    size: block.size.unwrap_or(stats.total_size),
+                    difficulty: block.difficulty,
+                    masternodes,
+                    credit_pool: block
+                        .cb_tx
+                        .map(|cb| (cb.credit_pool_balance * 1e8).round() as i64)
+                        .unwrap_or(0),
+                },
+            ));
+        }
+
+        if records.is_empty() {
+            return Ok(true);
+        }
+
+        let top = records.last().map(|(h, _)| *h).unwrap_or(0);
+        self.charts
+            .blocking(move |store| store.insert(&records))
+            .await?;
+
+        if top == node_height || top % 10_000 < SYNC_BATCH {
+            tracing::info!("Charts at height {} (node at {})", top, node_height);
+        }
+
+        Ok(top < node_height)
+    }
+}
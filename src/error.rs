@@ -1,4 +1,4 @@
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use serde_json::json;
 use std::fmt;
@@ -8,15 +8,34 @@ pub enum AppError {
     Rpc { code: i32, message: String },
     NotFound(String),
     BadRequest(String),
+    /// Temporarily unable to answer, e.g. while the local index is syncing
+    Unavailable(String),
     Internal(String),
 }
 
+/// Seconds clients are asked to wait before retrying an `Unavailable` response
+const RETRY_AFTER_SECS: u64 = 30;
+
+impl AppError {
+    /// Whether the node rejected an address RPC because it runs without `-addressindex`
+    pub fn is_address_index_missing(&self) -> bool {
+        match self {
+            AppError::Rpc { code, message } => {
+                *code == -5 && message.contains("No information available for address")
+                    || message.contains("ddress index not enabled")
+            }
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AppError::Rpc { code, message } => write!(f, "RPC error {}: {}", code, message),
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
             AppError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            AppError::Unavailable(msg) => write!(f, "Unavailable: {}", msg),
             AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -38,9 +57,20 @@ impl IntoResponse for AppError {
             }
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            AppError::Unavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
             AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
         };
 
+        if let AppError::Unavailable(_) = self {
+            let body = json!({ "error": message, "retry_after": RETRY_AFTER_SECS });
+            return (
+                status,
+                [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+                axum::Json(body),
+            )
+                .into_response();
+        }
+
         let body = json!({ "error": message });
         (status, axum::Json(body)).into_response()
     }
@@ -57,3 +87,39 @@ impl From<serde_json::Error> for AppError {
         AppError::Internal(format!("JSON error: {}", e))
     }
 }
+
+impl From<redb::Error> for AppError {
+    fn from(e: redb::Error) -> Self {
+        AppError::Internal(format!("Index storage error: {}", e))
+    }
+}
+
+impl From<redb::DatabaseError> for AppError {
+    fn from(e: redb::DatabaseError) -> Self {
+        redb::Error::from(e).into()
+    }
+}
+
+impl From<redb::TransactionError> for AppError {
+    fn from(e: redb::TransactionError) -> Self {
+        redb::Error::from(e).into()
+    }
+}
+
+impl From<redb::TableError> for AppError {
+    fn from(e: redb::TableError) -> Self {
+        redb::Error::from(e).into()
+    }
+}
+
+impl From<redb::StorageError> for AppError {
+    fn from(e: redb::StorageError) -> Self {
+        redb::Error::from(e).into()
+    }
+}
+
+impl From<redb::CommitError> for AppError {
+    fn from(e: redb::CommitError) -> Self {
+        redb::Error::from(e).into()
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::IpAddr;
+
+use flate2::read::GzDecoder;
+
+use crate::AppError;
+
+/// One row of an ip2asn table
+struct IpRange {
+    start: u128,
+    end: u128,
+    asn: u32,
+    /// ISO 3166 code, or None for unrouted and reserved ranges
+    country: Option<[u8; 2]>,
+}
+
+/// Autonomous system and country of an IP address
+#[derive(Debug, Clone, Default)]
+pub struct IpInfo {
+    pub country: Option<String>,
+    pub asn: Option<u32>,
+    /// AS description, or what the range is reserved for
+    pub name: Option<String>,
+}
+
+/// IP-to-ASN/country tables in the ip2asn TSV layout, looked up offline
+pub struct GeoIpDb {
+    /// Sorted by start
+    ranges: Vec<IpRange>,
+    names: HashMap<u32, String>,
+    /// Reserved ranges carry AS 0, so they're named per range
+    reserved: HashMap<u128, String>,
+}
+
+impl GeoIpDb {
+    /// Read the ip2asn table at `path`, plain or gzipped
+    pub fn load(path: &str) -> Result<Self, AppError> {
+        let mut db = Self {
+            ranges: Vec::new(),
+            names: HashMap::new(),
+            reserved: HashMap::new(),
+        };
+        let text = std::fs::read(path)
+            .and_then(|bytes| decompress(&bytes))
+            .map_err(|e| AppError::Internal(format!("Failed to read {}: {}", path, e)))?;
+        db.ranges = db
+            .parse(&text)
+            .map_err(|e| AppError::Internal(format!("{}: {}", path, e)))?;
+        Ok(db)
+    }
+
+    pub fn lookup(&self, ip: IpAddr) -> Option<IpInfo> {
+        let ip = to_u128(ip);
+        let i = self.ranges.partition_point(|r| r.start <= ip).checked_sub(1)?;
+        let range = Some(&self.ranges[i]).filter(|r| ip <= r.end)?;
+
+        let name = if range.asn == 0 {
+            self.reserved.get(&range.start).cloned()
+        } else {
+            self.names.get(&range.asn).cloned()
+        };
+        Some(IpInfo {
+            country: range
+                .country
+                .map(|c| String::from_utf8_lossy(&c).into_owned()),
+            asn: (range.asn != 0).then_some(range.asn),
+            name,
+        })
+    }
+
+    fn parse(&mut self, text: &str) -> Result<Vec<IpRange>, String> {
+        let mut table = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad = || format!("line {}: expected start, end, AS, country, description", n + 1);
+            let mut cols = line.splitn(5, '\t');
+            let mut next = || cols.next().ok_or_else(bad);
+            let start: IpAddr = next()?.parse().map_err(|_| bad())?;
+            let end: IpAddr = next()?.parse().map_err(|_| bad())?;
+            let asn: u32 = next()?.parse().map_err(|_| bad())?;
+            let country = match next()?.as_bytes() {
+                [a, b] => Some([*a, *b]),
+                _ => None,
+            };
+            let description = next().unwrap_or_default();
+
+            let (start, end) = (to_u128(start), to_u128(end));
+            if asn == 0 {
+                if !description.is_empty() && description != "Not routed" {
+                    self.reserved.insert(start, description.to_string());
+                }
+            } else {
+                self.names
+                    .entry(asn)
+                    .or_insert_with(|| description.to_string());
+            }
+            table.push(IpRange {
+                start,
+                end,
+                asn,
+                country,
+            });
+        }
+        table.sort_by_key(|r| r.start);
+        Ok(table)
+    }
+}
+
+/// Tables are read as text unless they carry the gzip magic bytes
+fn decompress(bytes: &[u8]) -> std::io::Result<String> {
+    if !bytes.starts_with(&[0x1f, 0x8b]) {
+        return String::from_utf8(bytes.to_vec())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+    }
+    let mut text = String::new();
+    GzDecoder::new(bytes).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// IPv4 addresses are kept IPv6-mapped, so both families share one table
+fn to_u128(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(v4) => u128::from(v4.to_ipv6_mapped()),
+        IpAddr::V6(v6) => u128::from(v6),
+    }
+}
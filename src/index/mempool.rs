@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::AddressIndex;
+use crate::mempool;
+use crate::rpc::types::{RpcAddressMempoolEntry, RpcTransaction};
+use crate::AppError;
+use crate::AppState;
+
+/// Address deltas for the whole mempool, equivalent to `getaddressmempool` for every
+/// address. Used with the local index, where the node has no `-addressindex`.
+/// Spent outputs are resolved from the transaction itself when the node reports them,
+/// then from other mempool transactions, then from the index's UTXO set.
+pub async fn scan(
+    state: &AppState,
+    index: &AddressIndex,
+) -> Result<Arc<Vec<RpcAddressMempoolEntry>>, AppError> {
+    if let Some(cached) = state.cache.mempool_deltas.get("mempool").await {
+        return Ok(cached);
+    }
+
+    let pool = state.rpc.get_raw_mempool_verbose().await?;
+    let txids: Vec<String> = pool.keys().cloned().collect();
+    let mut decoded = mempool::transactions(state, &txids).await?;
+    let txs: Vec<(Arc<RpcTransaction>, u64)> = pool
+        .into_iter()
+        .filter_map(|(txid, entry)| Some((decoded.remove(&txid)?, entry.time)))
+        .collect();
+
+    // Outputs created in the mempool, for spends of unconfirmed parents
+    let mut created: HashMap<(&str, u32), (&str, i64)> = HashMap::new();
+    for (tx, _) in &txs {
+        for output in &tx.vout {
+            if let Some(ref address) = output.script_pub_key.address {
+                created.insert((tx.txid.as_str(), output.n), (address, output.value_sat));
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    // (txid, input index, prevtxid, prevout, time) for spends only the index can resolve
+    let mut unresolved: Vec<(String, u32, String, u32, u64)> = Vec::new();
+
+    for (tx, time) in &txs {
+        for (i, input) in tx.vin.iter().enumerate() {
+            let (Some(prev_txid), Some(prev_n)) = (&input.txid, input.vout) else {
+                continue;
+            };
+            let spent = match (&input.address, input.value_sat) {
+                (Some(address), Some(satoshis)) => Some((address.as_str(), satoshis)),
+                _ => created.get(&(prev_txid.as_str(), prev_n)).copied(),
+            };
+            match spent {
+                Some((address, satoshis)) => entries.push(RpcAddressMempoolEntry {
+                    address: address.to_string(),
+                    txid: tx.txid.clone(),
+                    index: i as u32,
+                    satoshis: -satoshis,
+                    timestamp: *time,
+                    prevtxid: Some(prev_txid.clone()),
+                    prevout: Some(prev_n),
+                }),
+                None => unresolved.push((tx.txid.clone(), i as u32, prev_txid.clone(), prev_n, *time)),
+            }
+        }
+
+        for output in &tx.vout {
+            let Some(ref address) = output.script_pub_key.address else {
+                continue;
+            };
+            entries.push(RpcAddressMempoolEntry {
+                address: address.clone(),
+                txid: tx.txid.clone(),
+                index: output.n,
+                satoshis: output.value_sat,
+                timestamp: *time,
+                prevtxid: None,
+                prevout: None,
+            });
+        }
+    }
+
+    if !unresolved.is_empty() {
+        let outpoints: Vec<(String, u32)> = unresolved
+            .iter()
+            .map(|(_, _, prev_txid, prev_n, _)| (prev_txid.clone(), *prev_n))
+            .collect();
+        let outputs = index.blocking(move |store| store.outputs(&outpoints)).await?;
+        for ((txid, i, prev_txid, prev_n, time), output) in unresolved.into_iter().zip(outputs) {
+            let Some(output) = output else {
+                continue;
+            };
+            entries.push(RpcAddressMempoolEntry {
+                address: output.address,
+                txid,
+                index: i,
+                satoshis: -output.satoshis,
+                timestamp: time,
+                prevtxid: Some(prev_txid),
+                prevout: Some(prev_n),
+            });
+        }
+    }
+
+    let entries = Arc::new(entries);
+    state
+        .cache
+        .mempool_deltas
+        .insert("mempool".to_string(), entries.clone())
+        .await;
+    Ok(entries)
+}
@@ -0,0 +1,163 @@
+mod mempool;
+mod richlist;
+mod store;
+mod sync;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+pub use richlist::RichList;
+pub use store::IndexStore;
+pub use sync::IndexSyncer;
+
+use crate::rpc::types::{
+    RpcAddressBalance, RpcAddressDelta, RpcAddressMempoolEntry, RpcAddressUtxo,
+};
+use crate::AppError;
+use crate::AppState;
+
+/// Optional on-disk address index. Blocks are ingested through `getblock <hash> 2`
+/// so address queries can be answered without `-addressindex`/`-spentindex` on the node.
+pub struct AddressIndex {
+    store: Arc<IndexStore>,
+    /// Latest rich list, rebuilt in the background
+    richlist: RwLock<Option<Arc<RichList>>>,
+    /// Set once the syncer has reached the node's tip
+    synced: AtomicBool,
+}
+
+impl AddressIndex {
+    pub fn open(path: &str) -> Result<Self, AppError> {
+        let store = IndexStore::open(path)?;
+        Ok(Self {
+            store: Arc::new(store),
+            richlist: RwLock::new(None),
+            synced: AtomicBool::new(false),
+        })
+    }
+
+    /// Whether the index has caught up with the node and can answer queries
+    pub fn is_synced(&self) -> bool {
+        self.synced.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_synced(&self) {
+        self.synced.store(true, Ordering::Relaxed);
+    }
+
+    /// Run a store operation on the blocking thread pool
+    pub(crate) async fn blocking<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&IndexStore) -> Result<T, AppError> + Send + 'static,
+    {
+        let store = self.store.clone();
+        tokio::task::spawn_blocking(move || f(&store))
+            .await
+            .map_err(|e| AppError::Internal(format!("Index task failed: {}", e)))?
+    }
+
+    pub async fn balance(&self, address: &str) -> Result<RpcAddressBalance, AppError> {
+        let address = address.to_string();
+        let (balance, received, immature) =
+            self.blocking(move |store| store.balance(&address)).await?;
+        Ok(RpcAddressBalance {
+            balance,
+            balance_immature: immature,
+            balance_spendable: balance - immature,
+            received,
+        })
+    }
+
+    pub async fn txids(&self, address: &str) -> Result<Vec<String>, AppError> {
+        let address = address.to_string();
+        self.blocking(move |store| store.txids(&address)).await
+    }
+
+    pub async fn utxos(&self, address: &str) -> Result<Vec<RpcAddressUtxo>, AppError> {
+        let address = address.to_string();
+        self.blocking(move |store| store.utxos(&address)).await
+    }
+
+    pub async fn deltas(
+        &self,
+        address: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Result<Vec<RpcAddressDelta>, AppError> {
+        let address = address.to_string();
+        self.blocking(move |store| store.deltas(&address, start, end))
+            .await
+    }
+}
+
+// Address lookups go through the local index once it has caught up with the node
+// and fall back to the node's address index RPCs otherwise.
+impl AppState {
+    fn synced_index(&self) -> Option<&Arc<AddressIndex>> {
+        self.index.as_ref().filter(|index| index.is_synced())
+    }
+
+    /// While the local index is still syncing, a node without `-addressindex` is
+    /// reported as temporarily unavailable rather than as an upstream error.
+    fn node_fallback<T>(&self, result: Result<T, AppError>) -> Result<T, AppError> {
+        match result {
+            Err(e) if self.index.is_some() && e.is_address_index_missing() => Err(
+                AppError::Unavailable("The address index is still syncing".into()),
+            ),
+            result => result,
+        }
+    }
+
+    pub async fn get_address_balance(&self, address: &str) -> Result<RpcAddressBalance, AppError> {
+        match self.synced_index() {
+            Some(index) => index.balance(address).await,
+            None => self.node_fallback(self.rpc.get_address_balance(address).await),
+        }
+    }
+
+    pub async fn get_address_txids(&self, address: &str) -> Result<Vec<String>, AppError> {
+        match self.synced_index() {
+            Some(index) => index.txids(address).await,
+            None => self.node_fallback(self.rpc.get_address_txids(address).await),
+        }
+    }
+
+    pub async fn get_address_utxos(&self, address: &str) -> Result<Vec<RpcAddressUtxo>, AppError> {
+        match self.synced_index() {
+            Some(index) => index.utxos(address).await,
+            None => self.node_fallback(self.rpc.get_address_utxos(address).await),
+        }
+    }
+
+    pub async fn get_address_deltas(
+        &self,
+        address: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Result<Vec<RpcAddressDelta>, AppError> {
+        match self.synced_index() {
+            Some(index) => index.deltas(address, start, end).await,
+            None => self.node_fallback(self.rpc.get_address_deltas(address, start, end).await),
+        }
+    }
+
+    /// Unconfirmed deltas for an address. With the local index the node usually has no
+    /// `getaddressmempool`, so the mempool is scanned instead.
+    pub async fn get_address_mempool(
+        &self,
+        address: &str,
+    ) -> Result<Vec<RpcAddressMempoolEntry>, AppError> {
+        match self.index {
+            Some(ref index) => {
+                let entries = mempool::scan(self, index).await?;
+                Ok(entries
+                    .iter()
+                    .filter(|e| e.address == address)
+                    .cloned()
+                    .collect())
+            }
+            None => self.rpc.get_address_mempool(address).await,
+        }
+    }
+}
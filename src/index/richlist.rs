@@ -0,0 +1,129 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{AddressIndex, IndexStore};
+use crate::models::address::{DistributionBucket, RichListEntry};
+use crate::AppError;
+
+const COIN: i64 = 100_000_000;
+
+/// Bucket lower bounds in DASH: 0–1, 1–10, … 100k+
+const BUCKET_BOUNDS: [i64; 7] = [0, 1, 10, 100, 1_000, 10_000, 100_000];
+
+/// Largest holders and balance distribution, rebuilt periodically from the index
+pub struct RichList {
+    pub height: u64,
+    pub updated_at: u64,
+    pub total_addresses: u64,
+    pub total_sat: i64,
+    pub top: Vec<RichListEntry>,
+    pub distribution: Vec<DistributionBucket>,
+}
+
+impl RichList {
+    fn build(store: &IndexStore, size: usize) -> Result<Self, AppError> {
+        let height = store.tip()?.map(|(h, _)| h).unwrap_or(0);
+
+        let mut heap: BinaryHeap<Reverse<(i64, String)>> = BinaryHeap::with_capacity(size + 1);
+        let mut counts = [0u64; BUCKET_BOUNDS.len()];
+        let mut sums = [0i64; BUCKET_BOUNDS.len()];
+        let mut total_addresses = 0u64;
+        let mut total_sat = 0i64;
+
+        store.for_each_balance(|address, balance| {
+            if balance <= 0 {
+                return;
+            }
+            total_addresses += 1;
+            total_sat += balance;
+
+            let bucket = BUCKET_BOUNDS
+                .iter()
+                .rposition(|min| balance >= min * COIN)
+                .unwrap_or(0);
+            counts[bucket] += 1;
+            sums[bucket] += balance;
+
+            if heap.len() < size {
+                heap.push(Reverse((balance, address.to_string())));
+            } else if heap.peek().is_some_and(|Reverse((min, _))| balance > *min) {
+                heap.pop();
+                heap.push(Reverse((balance, address.to_string())));
+            }
+        })?;
+
+        let share = |sat: i64| {
+            if total_sat > 0 {
+                sat as f64 / total_sat as f64 * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        let top = heap
+            .into_sorted_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, Reverse((balance, address)))| RichListEntry {
+                rank: i + 1,
+                address,
+                balance: balance as f64 / 1e8,
+                balance_sat: balance,
+                share: share(balance),
+            })
+            .collect();
+
+        let distribution = BUCKET_BOUNDS
+            .iter()
+            .enumerate()
+            .map(|(i, min)| DistributionBucket {
+                min: *min as f64,
+                max: BUCKET_BOUNDS.get(i + 1).map(|max| *max as f64),
+                addresses: counts[i],
+                balance: sums[i] as f64 / 1e8,
+                balance_sat: sums[i],
+                share: share(sums[i]),
+            })
+            .collect();
+
+        Ok(Self {
+            height,
+            updated_at: chrono::Utc::now().timestamp() as u64,
+            total_addresses,
+            total_sat,
+            top,
+            distribution,
+        })
+    }
+}
+
+impl AddressIndex {
+    pub fn richlist(&self) -> Option<Arc<RichList>> {
+        self.richlist.read().unwrap().clone()
+    }
+
+    /// Rebuild the rich list every `interval`, keeping the `size` largest holders. The
+    /// first build waits for the initial sync, so a partial list is never served.
+    pub async fn run_richlist(self: Arc<Self>, interval: Duration, size: usize) {
+        while !self.is_synced() {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match self.blocking(move |store| RichList::build(store, size)).await {
+                Ok(list) => {
+                    tracing::debug!(
+                        "Rich list rebuilt at height {} ({} addresses)",
+                        list.height,
+                        list.total_addresses
+                    );
+                    *self.richlist.write().unwrap() = Some(Arc::new(list));
+                }
+                Err(e) => tracing::warn!("Rich list rebuild failed: {}", e),
+            }
+        }
+    }
+}
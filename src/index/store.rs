@@ -0,0 +1,366 @@
+use redb::{Database, Durability, ReadableTable, TableDefinition};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::rpc::types::{RpcAddressDelta, RpcAddressUtxo, RpcBlock};
+use crate::AppError;
+
+/// Block hash by height for every indexed block
+const BLOCKS: TableDefinition<u64, &str> = TableDefinition::new("blocks");
+/// Undo data by height, used to disconnect blocks on reorg
+const UNDO: TableDefinition<u64, &[u8]> = TableDefinition::new("undo");
+/// Unspent outputs by outpoint ("txid:n")
+const UTXOS: TableDefinition<&str, &[u8]> = TableDefinition::new("utxos");
+/// (address, outpoint) for every unspent output of an address
+const ADDR_UTXOS: TableDefinition<(&str, &str), ()> = TableDefinition::new("addr_utxos");
+/// (address, height, tx position) -> (txid, net satoshi delta)
+const ADDR_TXS: TableDefinition<(&str, u64, u32), (&str, i64)> = TableDefinition::new("addr_txs");
+/// address -> (balance, total received) in satoshis
+const ADDR_BALANCE: TableDefinition<&str, (i64, i64)> = TableDefinition::new("addr_balance");
+
+/// Coinbase outputs can't be spent until they have this many confirmations
+const COINBASE_MATURITY: u64 = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StoredOutput {
+    pub address: String,
+    pub script: String,
+    pub satoshis: i64,
+    pub height: u64,
+    pub coinbase: bool,
+}
+
+/// Everything needed to undo a connected block
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct BlockUndo {
+    created: Vec<String>,
+    spent: Vec<(String, StoredOutput)>,
+    touched: Vec<(String, u32)>,
+}
+
+pub struct IndexStore {
+    db: Database,
+}
+
+fn outpoint(txid: &str, n: u32) -> String {
+    format!("{}:{}", txid, n)
+}
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_json::to_vec(value).expect("index record serialization cannot fail")
+}
+
+fn decode<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<T, AppError> {
+    serde_json::from_slice(bytes)
+        .map_err(|e| AppError::Internal(format!("Corrupted index record: {}", e)))
+}
+
+impl IndexStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AppError> {
+        let db = Database::create(path)?;
+
+        // Make sure every table exists so read transactions never fail on a fresh file
+        let txn = db.begin_write()?;
+        {
+            txn.open_table(BLOCKS)?;
+            txn.open_table(UNDO)?;
+            txn.open_table(UTXOS)?;
+            txn.open_table(ADDR_UTXOS)?;
+            txn.open_table(ADDR_TXS)?;
+            txn.open_table(ADDR_BALANCE)?;
+        }
+        txn.commit()?;
+
+        Ok(Self { db })
+    }
+
+    /// Highest indexed block as (height, hash)
+    pub fn tip(&self) -> Result<Option<(u64, String)>, AppError> {
+        let txn = self.db.begin_read()?;
+        let blocks = txn.open_table(BLOCKS)?;
+        let last = blocks.last()?;
+        Ok(last.map(|(h, hash)| (h.value(), hash.value().to_string())))
+    }
+
+    pub fn block_hash(&self, height: u64) -> Result<Option<String>, AppError> {
+        let txn = self.db.begin_read()?;
+        let blocks = txn.open_table(BLOCKS)?;
+        let hash = blocks.get(height)?;
+        Ok(hash.map(|h| h.value().to_string()))
+    }
+
+    /// Connect a run of consecutive blocks (fetched at verbosity 2) in one write transaction.
+    /// `durable` should be false while catching up far behind the tip.
+    pub fn connect_blocks(&self, blocks: &[RpcBlock], durable: bool) -> Result<(), AppError> {
+        let mut txn = self.db.begin_write()?;
+        if !durable {
+            txn.set_durability(Durability::Eventual);
+        }
+        {
+            let mut block_table = txn.open_table(BLOCKS)?;
+            let mut undo_table = txn.open_table(UNDO)?;
+            let mut utxos = txn.open_table(UTXOS)?;
+            let mut addr_utxos = txn.open_table(ADDR_UTXOS)?;
+            let mut addr_txs = txn.open_table(ADDR_TXS)?;
+            let mut balances = txn.open_table(ADDR_BALANCE)?;
+
+            for block in blocks {
+                let mut undo = BlockUndo::default();
+                let txs = block.transactions().ok_or_else(|| {
+                    AppError::Internal(format!(
+                        "Block {} at height {} has no decodable transactions",
+                        block.hash, block.height
+                    ))
+                })?;
+
+                for (pos, tx) in txs.iter().enumerate() {
+                    let pos = pos as u32;
+                    let is_coinbase = tx.vin.iter().any(|v| v.coinbase.is_some());
+                    // Net delta and received amount per address touched by this tx
+                    let mut touched: Vec<String> = Vec::new();
+                    let mut deltas: HashMap<String, (i64, i64)> = HashMap::new();
+
+                    for input in &tx.vin {
+                        let (Some(prev_txid), Some(prev_n)) = (&input.txid, input.vout) else {
+                            continue;
+                        };
+                        let key = outpoint(prev_txid, prev_n);
+                        let removed = utxos.remove(key.as_str())?.map(|v| v.value().to_vec());
+                        if let Some(bytes) = removed {
+                            let out: StoredOutput = decode(&bytes)?;
+                            addr_utxos.remove((out.address.as_str(), key.as_str()))?;
+                            if !deltas.contains_key(&out.address) {
+                                touched.push(out.address.clone());
+                            }
+                            deltas.entry(out.address.clone()).or_default().0 -= out.satoshis;
+                            undo.spent.push((key, out));
+                        }
+                    }
+
+                    for output in &tx.vout {
+                        let Some(ref address) = output.script_pub_key.address else {
+                            continue;
+                        };
+                        let key = outpoint(&tx.txid, output.n);
+                        let out = StoredOutput {
+                            address: address.clone(),
+                            script: output.script_pub_key.hex.clone(),
+                            satoshis: output.value_sat,
+                            height: block.height,
+                            coinbase: is_coinbase,
+                        };
+                        utxos.insert(key.as_str(), encode(&out).as_slice())?;
+                        addr_utxos.insert((address.as_str(), key.as_str()), ())?;
+                        if !deltas.contains_key(address) {
+                            touched.push(address.clone());
+                        }
+                        let entry = deltas.entry(address.clone()).or_default();
+                        entry.0 += output.value_sat;
+                        entry.1 += output.value_sat;
+                        undo.created.push(key);
+                    }
+
+                    for address in touched {
+                        let (delta, received) = deltas[&address];
+                        addr_txs.insert(
+                            (address.as_str(), block.height, pos),
+                            (tx.txid.as_str(), delta),
+                        )?;
+                        adjust_balance(&mut balances, &address, delta, received)?;
+                        undo.touched.push((address, pos));
+                    }
+                }
+
+                block_table.insert(block.height, block.hash.as_str())?;
+                undo_table.insert(block.height, encode(&undo).as_slice())?;
+            }
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Disconnect the current tip block, restoring the outputs it spent
+    pub fn disconnect_tip(&self) -> Result<Option<(u64, String)>, AppError> {
+        let Some((height, hash)) = self.tip()? else {
+            return Ok(None);
+        };
+
+        let txn = self.db.begin_write()?;
+        {
+            let mut block_table = txn.open_table(BLOCKS)?;
+            let mut undo_table = txn.open_table(UNDO)?;
+            let mut utxos = txn.open_table(UTXOS)?;
+            let mut addr_utxos = txn.open_table(ADDR_UTXOS)?;
+            let mut addr_txs = txn.open_table(ADDR_TXS)?;
+            let mut balances = txn.open_table(ADDR_BALANCE)?;
+
+            let undo: BlockUndo = match undo_table.remove(height)? {
+                Some(bytes) => decode(bytes.value())?,
+                None => BlockUndo::default(),
+            };
+
+            // Restore spent outputs first so outputs created and spent in this
+            // same block are removed again by the loop below
+            for (key, out) in &undo.spent {
+                utxos.insert(key.as_str(), encode(out).as_slice())?;
+                addr_utxos.insert((out.address.as_str(), key.as_str()), ())?;
+                adjust_balance(&mut balances, &out.address, out.satoshis, 0)?;
+            }
+
+            for key in &undo.created {
+                let removed = utxos.remove(key.as_str())?.map(|v| v.value().to_vec());
+                if let Some(bytes) = removed {
+                    let out: StoredOutput = decode(&bytes)?;
+                    addr_utxos.remove((out.address.as_str(), key.as_str()))?;
+                    adjust_balance(&mut balances, &out.address, -out.satoshis, -out.satoshis)?;
+                }
+            }
+
+            for (address, pos) in &undo.touched {
+                addr_txs.remove((address.as_str(), height, *pos))?;
+            }
+
+            block_table.remove(height)?;
+        }
+        txn.commit()?;
+
+        Ok(Some((height, hash)))
+    }
+
+    /// Returns (balance, received, immature) in satoshis
+    pub fn balance(&self, address: &str) -> Result<(i64, i64, i64), AppError> {
+        let tip = self.tip()?.map(|(h, _)| h).unwrap_or(0);
+        let txn = self.db.begin_read()?;
+        let balances = txn.open_table(ADDR_BALANCE)?;
+        let (balance, received) = balances.get(address)?.map(|v| v.value()).unwrap_or((0, 0));
+
+        let immature = self
+            .read_utxos(&txn, address)?
+            .iter()
+            .filter(|(_, out)| out.coinbase && tip < out.height + COINBASE_MATURITY)
+            .map(|(_, out)| out.satoshis)
+            .sum();
+
+        Ok((balance, received, immature))
+    }
+
+    /// Txids touching an address, oldest first
+    pub fn txids(&self, address: &str) -> Result<Vec<String>, AppError> {
+        let mut txids: Vec<String> = Vec::new();
+        for delta in self.deltas(address, None, None)? {
+            if txids.last() != Some(&delta.txid) {
+                txids.push(delta.txid);
+            }
+        }
+        Ok(txids)
+    }
+
+    /// Per-transaction deltas for an address, optionally bounded by height (inclusive)
+    pub fn deltas(
+        &self,
+        address: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Result<Vec<RpcAddressDelta>, AppError> {
+        let txn = self.db.begin_read()?;
+        let addr_txs = txn.open_table(ADDR_TXS)?;
+        let range =
+            (address, start.unwrap_or(0), 0u32)..=(address, end.unwrap_or(u64::MAX), u32::MAX);
+
+        let mut deltas = Vec::new();
+        for entry in addr_txs.range(range)? {
+            let (key, value) = entry?;
+            let (_, height, pos) = key.value();
+            let (txid, satoshis) = value.value();
+            deltas.push(RpcAddressDelta {
+                satoshis,
+                txid: txid.to_string(),
+                index: 0,
+                blockindex: pos,
+                height,
+                address: address.to_string(),
+            });
+        }
+        Ok(deltas)
+    }
+
+    pub fn utxos(&self, address: &str) -> Result<Vec<RpcAddressUtxo>, AppError> {
+        let txn = self.db.begin_read()?;
+        let utxos = self
+            .read_utxos(&txn, address)?
+            .into_iter()
+            .map(|(key, out)| {
+                let (txid, n) = key.split_once(':').unwrap_or((key.as_str(), "0"));
+                RpcAddressUtxo {
+                    address: out.address.clone(),
+                    txid: txid.to_string(),
+                    output_index: n.parse().unwrap_or(0),
+                    script: out.script,
+                    satoshis: out.satoshis,
+                    height: out.height,
+                }
+            })
+            .collect();
+        Ok(utxos)
+    }
+
+    /// Look up unspent outputs by outpoint; spent or unknown ones come back as None
+    pub fn outputs(&self, outpoints: &[(String, u32)]) -> Result<Vec<Option<StoredOutput>>, AppError> {
+        let txn = self.db.begin_read()?;
+        let utxos = txn.open_table(UTXOS)?;
+        let mut result = Vec::with_capacity(outpoints.len());
+        for (txid, n) in outpoints {
+            let key = outpoint(txid, *n);
+            result.push(match utxos.get(key.as_str())? {
+                Some(bytes) => Some(decode(bytes.value())?),
+                None => None,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Visit the balance of every address ever seen, including emptied ones
+    pub fn for_each_balance(&self, mut f: impl FnMut(&str, i64)) -> Result<(), AppError> {
+        let txn = self.db.begin_read()?;
+        let balances = txn.open_table(ADDR_BALANCE)?;
+        for entry in balances.iter()? {
+            let (address, value) = entry?;
+            f(address.value(), value.value().0);
+        }
+        Ok(())
+    }
+
+    fn read_utxos(
+        &self,
+        txn: &redb::ReadTransaction,
+        address: &str,
+    ) -> Result<Vec<(String, StoredOutput)>, AppError> {
+        let addr_utxos = txn.open_table(ADDR_UTXOS)?;
+        let utxos = txn.open_table(UTXOS)?;
+
+        let mut result = Vec::new();
+        for entry in addr_utxos.range((address, "")..)? {
+            let (key, _) = entry?;
+            let (addr, op) = key.value();
+            if addr != address {
+                break;
+            }
+            if let Some(bytes) = utxos.get(op)? {
+                result.push((op.to_string(), decode(bytes.value())?));
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn adjust_balance(
+    balances: &mut redb::Table<&str, (i64, i64)>,
+    address: &str,
+    delta: i64,
+    received: i64,
+) -> Result<(), AppError> {
+    let (balance, total) = balances.get(address)?.map(|v| v.value()).unwrap_or((0, 0));
+    balances.insert(address, (balance + delta, total + received))?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::AddressIndex;
+use crate::rpc::types::RpcBlock;
+use crate::rpc::DashRpcClient;
+use crate::AppError;
+
+/// Blocks fetched and committed together while catching up
+const SYNC_BATCH: u64 = 50;
+
+/// Follows the node's active chain and feeds connected blocks into the store
+pub struct IndexSyncer {
+    rpc: Arc<DashRpcClient>,
+    index: Arc<AddressIndex>,
+}
+
+impl IndexSyncer {
+    pub fn new(rpc: Arc<DashRpcClient>, index: Arc<AddressIndex>) -> Self {
+        Self { rpc, index }
+    }
+
+    pub async fn run(self) {
+        tracing::info!("Address index syncer started");
+
+        loop {
+            match self.sync_step().await {
+                // Still catching up, go straight to the next batch
+                Ok(true) => continue,
+                Ok(false) => {
+                    if !self.index.is_synced() {
+                        tracing::info!("Address index caught up with the node");
+                        self.index.set_synced();
+                    }
+                }
+                Err(e) => tracing::warn!("Address index sync failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    }
+
+    /// Index the next batch of blocks. Returns true when more blocks are pending.
+    async fn sync_step(&self) -> Result<bool, AppError> {
+        let node_height = self.rpc.get_block_count().await?;
+        let local_tip = self.index.blocking(|store| store.tip()).await?;
+
+        let next_height = match local_tip {
+            Some((height, hash)) => {
+                // Rewind if our tip is no longer on the node's active chain
+                if height > node_height || self.rpc.get_block_hash(height).await? != hash {
+                    self.rewind().await?;
+                    return Ok(true);
+                }
+                height + 1
+            }
+            None => 0,
+        };
+
+        if next_height > node_height {
+            return Ok(false);
+        }
+
+        let last = (next_height + SYNC_BATCH - 1).min(node_height);
+        let mut blocks: Vec<RpcBlock> = Vec::with_capacity((last - next_height + 1) as usize);
+        for height in next_height..=last {
+            let hash = self.rpc.get_block_hash(height).await?;
+            let block = self.rpc.get_block(&hash, 2).await?;
+
+            let expected_prev = match blocks.last() {
+                Some(prev) => Some(prev.hash.clone()),
+                None if height > 0 => {
+                    self.index
+                        .blocking(move |store| store.block_hash(height - 1))
+                        .await?
+                }
+                None => None,
+            };
+            if block.previous_block_hash != expected_prev {
+                // The chain moved under us mid-batch; start over on the next step
+                break;
+            }
+            blocks.push(block);
+        }
+
+        if blocks.is_empty() {
+            return Ok(true);
+        }
+
+        let durable = last + SYNC_BATCH > node_height;
+        let connected = blocks.len();
+        let top = blocks.last().map(|b| b.height).unwrap_or(0);
+        self.index
+            .blocking(move |store| store.connect_blocks(&blocks, durable))
+            .await?;
+
+        if durable || top % 10_000 < SYNC_BATCH {
+            tracing::info!(
+                "Address index at height {} ({} blocks connected, node at {})",
+                top,
+                connected,
+                node_height
+            );
+        }
+
+        Ok(top < node_height)
+    }
+
+    /// Disconnect index blocks until the tip hash matches the node's chain again
+    async fn rewind(&self) -> Result<(), AppError> {
+        loop {
+            let Some((height, hash)) = self.index.blocking(|store| store.tip()).await? else {
+                return Ok(());
+            };
+            match self.rpc.get_block_hash(height).await {
+                Ok(node_hash) if node_hash == hash => return Ok(()),
+                Ok(_) => {}
+                // "Block height out of range": the node's chain is now shorter than ours
+                Err(AppError::Rpc { code: -8, .. }) => {}
+                // Anything else (timeout, auth, restart) is retried on the next pass
+                Err(e) => return Err(e),
+            }
+            self.index.blocking(|store| store.disconnect_tip()).await?;
+            tracing::info!(
+                "Address index disconnected block {} at height {}",
+                hash,
+                height
+            );
+        }
+    }
+}
@@ -1,10 +1,25 @@
 use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc};
 
 use crate::cache::AppCache;
+use crate::models::transaction::tx_type_label;
+use crate::rpc::types::RpcTransaction;
 use crate::rpc::DashRpcClient;
+use crate::zmq::{ZmqNotification, ZMQ_QUIET};
+use crate::AppError;
+
+/// Number of recent (height, hash) pairs kept to locate fork points
+const REORG_WINDOW: usize = 100;
+
+/// Cap on new mempool transactions fetched per poll, so a flood can't stall the loop
+const MAX_NEW_TXS_PER_POLL: usize = 200;
+
+/// Cap on unlocked mempool transactions whose lock state is checked per poll
+const MAX_LOCK_CHECKS_PER_POLL: usize = 500;
 
 #[derive(Clone, Debug, Serialize)]
 #[serde(tag = "type", content = "data")]
@@ -17,19 +32,60 @@ pub enum LiveEvent {
         chainlock: bool,
         credit_pool_balance: f64,
     },
+    BlockDisconnected {
+        hash: String,
+        height: u64,
+    },
+    Reorg {
+        fork_height: u64,
+        old_tip: String,
+        new_tip: String,
+        depth: u64,
+    },
     MempoolUpdate {
         size: u64,
         bytes: u64,
         total_fee: f64,
     },
+    NewTransaction {
+        txid: String,
+        tx_type: u32,
+        tx_type_label: String,
+        total_output: f64,
+        instantlock: bool,
+        addresses: Vec<String>,
+        pro_tx_hash: Option<String>,
+    },
+    InstantLock {
+        txid: String,
+    },
+    ChainLock {
+        height: u64,
+        hash: String,
+    },
+    GovernanceObject {
+        hash: String,
+    },
+    GovernanceVote {
+        hash: String,
+    },
 }
 
 pub struct LiveUpdater {
     rpc: Arc<DashRpcClient>,
     cache: Arc<AppCache>,
     tx: broadcast::Sender<LiveEvent>,
-    last_height: AtomicU64,
+    /// Recently seen active chain, oldest first
+    recent: VecDeque<(u64, String)>,
     last_mempool_size: AtomicU64,
+    zmq: Option<mpsc::Receiver<ZmqNotification>>,
+    last_zmq: Option<Instant>,
+    last_mempool_check: Instant,
+    /// Mempool txids seen so far; None until the first poll seeds it
+    mempool_txids: Option<HashSet<String>>,
+    /// Mempool txids already reported as InstantSend locked
+    locked_txids: HashSet<String>,
+    last_chainlock: Option<(u64, String)>,
 }
 
 impl LiveUpdater {
@@ -42,70 +98,419 @@ impl LiveUpdater {
             rpc,
             cache,
             tx,
-            last_height: AtomicU64::new(0),
+            recent: VecDeque::with_capacity(REORG_WINDOW + 1),
             last_mempool_size: AtomicU64::new(0),
+            zmq: None,
+            last_zmq: None,
+            last_mempool_check: Instant::now(),
+            mempool_txids: None,
+            locked_txids: HashSet::new(),
+            last_chainlock: None,
         }
     }
 
-    pub async fn run(self) {
+    /// Drive updates from ZMQ notifications, keeping polling as a fallback
+    pub fn with_zmq(mut self, rx: mpsc::Receiver<ZmqNotification>) -> Self {
+        self.zmq = Some(rx);
+        self
+    }
+
+    pub async fn run(mut self) {
         tracing::info!("Live updater started");
 
         let mut tick = 0u64;
+        let mut interval = tokio::time::interval(Duration::from_secs(2));
 
         loop {
-            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-            tick += 1;
-
-            // Check for new blocks every 2s
-            if let Ok(height) = self.rpc.get_block_count().await {
-                let prev = self.last_height.load(Ordering::Relaxed);
-                if prev > 0 && height > prev {
-                    // New block(s) detected
-                    for h in (prev + 1)..=height {
-                        if let Ok(hash) = self.rpc.get_block_hash(h).await {
-                            if let Ok(block) = self.rpc.get_block(&hash, 1).await {
-                                let credit_pool = block
-                                    .cb_tx
-                                    .as_ref()
-                                    .map(|cb| cb.credit_pool_balance)
-                                    .unwrap_or(0.0);
-
-                                let event = LiveEvent::NewBlock {
-                                    hash: block.hash.clone(),
-                                    height: block.height,
-                                    time: block.time,
-                                    n_tx: block.n_tx,
-                                    chainlock: block.chainlock,
-                                    credit_pool_balance: credit_pool,
-                                };
-
-                                let _ = self.tx.send(event);
-                                tracing::info!("New block {} at height {}", &hash[..16], h);
-                            }
+            tokio::select! {
+                _ = interval.tick() => {
+                    tick += 1;
+
+                    // Check for new blocks every 2s, or every 30s while ZMQ is delivering
+                    if !self.zmq_active() || tick.is_multiple_of(15) {
+                        if let Err(e) = self.follow_chain().await {
+                            tracing::debug!("Chain follower tick failed: {}", e);
                         }
                     }
-                    // Invalidate tip caches
-                    self.cache.latest_blocks.invalidate_all();
-                    self.cache.status.invalidate_all();
-                }
-                self.last_height.store(height, Ordering::Relaxed);
-            }
-
-            // Check mempool every 5 ticks (10s)
-            if tick % 5 == 0 {
-                if let Ok(mempool) = self.rpc.get_mempool_info().await {
-                    let prev_size = self.last_mempool_size.load(Ordering::Relaxed);
-                    if mempool.size != prev_size {
-                        let event = LiveEvent::MempoolUpdate {
-                            size: mempool.size,
-                            bytes: mempool.bytes,
-                            total_fee: mempool.total_fee,
-                        };
-                        let _ = self.tx.send(event);
-                        self.last_mempool_size.store(mempool.size, Ordering::Relaxed);
+
+                    // Diff mempool txs and chainlocks every tick, every 10s while ZMQ is delivering
+                    if !self.zmq_active() || tick.is_multiple_of(5) {
+                        if let Err(e) = self.poll_mempool_txs().await {
+                            tracing::debug!("Mempool tx poll failed: {}", e);
+                        }
+                        if let Err(e) = self.check_chainlock().await {
+                            tracing::debug!("Chainlock poll failed: {}", e);
+                        }
                     }
+
+                    // Check mempool every 5 ticks (10s)
+                    if tick.is_multiple_of(5) {
+                        self.check_mempool().await;
+                    }
+                }
+                Some(notification) = next_notification(&mut self.zmq) => {
+                    self.last_zmq = Some(Instant::now());
+                    self.handle_notification(notification).await;
+                }
+            }
+        }
+    }
+
+    fn zmq_active(&self) -> bool {
+        self.last_zmq.is_some_and(|t| t.elapsed() < ZMQ_QUIET)
+    }
+
+    async fn handle_notification(&mut self, notification: ZmqNotification) {
+        match notification {
+            ZmqNotification::HashBlock(hash) => {
+                tracing::debug!("ZMQ block {}", hash);
+                if let Err(e) = self.follow_chain().await {
+                    tracing::debug!("Chain follower failed on ZMQ block: {}", e);
+                }
+            }
+            ZmqNotification::HashChainLock(hash) => {
+                tracing::debug!("ZMQ chainlock {}", hash);
+                if let Err(e) = self.check_chainlock().await {
+                    tracing::debug!("Chainlock check failed on ZMQ chainlock: {}", e);
+                }
+            }
+            ZmqNotification::RawTx(txid) => {
+                self.announce_tx(&txid).await;
+                // Collapse bursts of transactions into one mempool refresh per second
+                if self.last_mempool_check.elapsed() >= Duration::from_secs(1) {
+                    self.check_mempool().await;
+                }
+            }
+            ZmqNotification::RawTxLock(txid) => {
+                self.announce_tx(&txid).await;
+                self.announce_lock(&txid);
+            }
+            ZmqNotification::GovernanceObject(hash) => {
+                let _ = self.tx.send(LiveEvent::GovernanceObject { hash });
+            }
+            ZmqNotification::GovernanceVote(hash) => {
+                let _ = self.tx.send(LiveEvent::GovernanceVote { hash });
+            }
+        }
+    }
+
+    async fn check_mempool(&mut self) {
+        self.last_mempool_check = Instant::now();
+        if let Ok(mempool) = self.rpc.get_mempool_info().await {
+            let prev_size = self.last_mempool_size.load(Ordering::Relaxed);
+            if mempool.size != prev_size {
+                let event = LiveEvent::MempoolUpdate {
+                    size: mempool.size,
+                    bytes: mempool.bytes,
+                    total_fee: mempool.total_fee,
+                };
+                let _ = self.tx.send(event);
+                self.last_mempool_size.store(mempool.size, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Diff the mempool txids against the previous poll, announcing new transactions
+    /// and InstantSend locks that arrived since then.
+    async fn poll_mempool_txs(&mut self) -> Result<(), AppError> {
+        let Some(ref mut known) = self.mempool_txids else {
+            // First poll: remember what's there without announcing it
+            let entries = self.rpc.get_raw_mempool_verbose().await?;
+            self.locked_txids = entries
+                .iter()
+                .filter(|(_, e)| e.instantlock)
+                .map(|(txid, _)| txid.clone())
+                .collect();
+            self.mempool_txids = Some(entries.into_keys().collect());
+            return Ok(());
+        };
+        let txids: HashSet<String> = self.rpc.get_raw_mempool_txids().await?.into_iter().collect();
+
+        // Forget transactions that left the mempool
+        known.retain(|txid| txids.contains(txid));
+        self.locked_txids.retain(|txid| txids.contains(txid));
+
+        let new_txids: Vec<String> = txids
+            .iter()
+            .filter(|txid| !known.contains(*txid))
+            .take(MAX_NEW_TXS_PER_POLL)
+            .cloned()
+            .collect();
+        // Only transactions still waiting for a lock need their verbose entry
+        let unlocked: Vec<String> = known
+            .iter()
+            .filter(|txid| !self.locked_txids.contains(*txid))
+            .take(MAX_LOCK_CHECKS_PER_POLL)
+            .cloned()
+            .collect();
+
+        for txid in &new_txids {
+            self.announce_tx(txid).await;
+        }
+
+        let entries = self.rpc.get_mempool_entries(&unlocked).await?;
+        for (txid, entry) in unlocked.iter().zip(entries) {
+            if entry.is_ok_and(|e| e.instantlock) {
+                self.announce_lock(txid);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Emit NewTransaction for a mempool tx we haven't reported yet
+    async fn announce_tx(&mut self, txid: &str) {
+        // Nothing is announced until the first poll has seeded the mempool snapshot
+        let Some(ref known) = self.mempool_txids else {
+            return;
+        };
+        if known.contains(txid) {
+            return;
+        }
+
+        // Only remember the txid once it was fetched, so failures are retried next poll
+        let tx = match self.rpc.get_raw_transaction(txid).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                tracing::debug!("Failed to fetch new mempool tx {}: {}", txid, e);
+                return;
+            }
+        };
+        if let Some(ref mut known) = self.mempool_txids {
+            known.insert(txid.to_string());
+        }
+
+        // ZMQ also publishes the transactions of connected blocks
+        if tx.blockhash.is_some() {
+            return;
+        }
+        if tx.instantlock {
+            self.locked_txids.insert(tx.txid.clone());
+        }
+        let _ = self.tx.send(new_transaction_event(&tx));
+    }
+
+    fn announce_lock(&mut self, txid: &str) {
+        if self.locked_txids.insert(txid.to_string()) {
+            let _ = self.tx.send(LiveEvent::InstantLock {
+                txid: txid.to_string(),
+            });
+        }
+    }
+
+    async fn check_chainlock(&mut self) -> Result<(), AppError> {
+        let cl = self.rpc.get_best_chain_lock().await?;
+        let current = (cl.height, cl.blockhash);
+        if self.last_chainlock.as_ref() == Some(&current) {
+            return Ok(());
+        }
+
+        // Blocks up to the new chainlock changed their chainlock flag
+        self.cache.latest_blocks.invalidate_all();
+        self.cache.status.invalidate_all();
+        let locked_from = self.last_chainlock.as_ref().map_or(0, |(h, _)| h + 1);
+        let tracked_from = self.recent.front().map_or(u64::MAX, |(h, _)| *h);
+        if locked_from < tracked_from {
+            // Some of the newly locked blocks are older than the tracked ones
+            self.cache.blocks.invalidate_all();
+        } else {
+            for (_, hash) in self
+                .recent
+                .iter()
+                .filter(|(h, _)| (locked_from..=current.0).contains(h))
+            {
+                self.cache.blocks.invalidate(hash).await;
+            }
+            self.cache.blocks.invalidate(&current.1).await;
+        }
+
+        let first = self.last_chainlock.is_none();
+        self.last_chainlock = Some(current.clone());
+        if !first {
+            let _ = self.tx.send(LiveEvent::ChainLock {
+                height: current.0,
+                hash: current.1,
+            });
+        }
+        Ok(())
+    }
+
+    /// Bring `recent` in line with the node's active chain, emitting events for
+    /// disconnected and newly connected blocks.
+    async fn follow_chain(&mut self) -> Result<(), AppError> {
+        let height = self.rpc.get_block_count().await?;
+        let tip_hash = self.rpc.get_block_hash(height).await?;
+
+        let Some((last_height, last_hash)) = self.recent.back().cloned() else {
+            // First tick: just remember where the chain is
+            self.recent.push_back((height, tip_hash));
+            return Ok(());
+        };
+
+        if last_height == height && last_hash == tip_hash {
+            return Ok(());
+        }
+
+        // Find the highest tracked block that is still on the active chain
+        let mut fork_height = None;
+        for (h, hash) in self.recent.iter().rev() {
+            if *h > height {
+                continue;
+            }
+            let node_hash = if *h == height {
+                tip_hash.clone()
+            } else {
+                self.rpc.get_block_hash(*h).await?
+            };
+            if &node_hash == hash {
+                fork_height = Some(*h);
+                break;
+            }
+        }
+
+        let fork_height = match fork_height {
+            Some(h) => h,
+            None => {
+                let oldest = self.recent.front().map(|(h, _)| *h).unwrap_or(0);
+                tracing::warn!(
+                    "Reorg deeper than {} tracked blocks, resyncing from height {}",
+                    self.recent.len(),
+                    oldest
+                );
+                oldest.saturating_sub(1)
+            }
+        };
+
+        if fork_height < last_height {
+            self.disconnect_to(fork_height, &tip_hash).await;
+        }
+
+        // Connect new blocks on top of the fork point
+        let mut invalidate_tip = fork_height < last_height;
+        for h in (fork_height + 1)..=height {
+            let hash = self.rpc.get_block_hash(h).await?;
+            let block = self.rpc.get_block(&hash, 1).await?;
+
+            if let Some((_, prev_hash)) = self.recent.back() {
+                if block.previous_block_hash.as_ref() != Some(prev_hash) {
+                    // Chain changed again while we were walking it, retry next tick
+                    break;
                 }
             }
+
+            let credit_pool = block
+                .cb_tx
+                .as_ref()
+                .map(|cb| cb.credit_pool_balance)
+                .unwrap_or(0.0);
+
+            let event = LiveEvent::NewBlock {
+                hash: block.hash.clone(),
+                height: block.height,
+                time: block.time,
+                n_tx: block.n_tx,
+                chainlock: block.chainlock,
+                credit_pool_balance: credit_pool,
+            };
+
+            let _ = self.tx.send(event);
+            tracing::info!("New block {} at height {}", &hash[..16], h);
+
+            self.recent.push_back((h, hash));
+            if self.recent.len() > REORG_WINDOW {
+                self.recent.pop_front();
+            }
+            invalidate_tip = true;
         }
+
+        if invalidate_tip {
+            // Invalidate tip caches
+            self.cache.latest_blocks.invalidate_all();
+            self.cache.status.invalidate_all();
+        }
+
+        Ok(())
+    }
+
+    /// Drop tracked blocks above `fork_height`, evicting them from the caches
+    async fn disconnect_to(&mut self, fork_height: u64, new_tip: &str) {
+        let old_tip = self
+            .recent
+            .back()
+            .map(|(_, hash)| hash.clone())
+            .unwrap_or_default();
+        let old_height = self.recent.back().map(|(h, _)| *h).unwrap_or(fork_height);
+
+        while let Some((h, hash)) = self.recent.back().cloned() {
+            if h <= fork_height {
+                break;
+            }
+            self.recent.pop_back();
+            self.evict_block(h, &hash).await;
+
+            let _ = self.tx.send(LiveEvent::BlockDisconnected {
+                hash: hash.clone(),
+                height: h,
+            });
+            tracing::info!("Block {} at height {} disconnected", &hash[..16], h);
+        }
+
+        let depth = old_height - fork_height;
+        let _ = self.tx.send(LiveEvent::Reorg {
+            fork_height,
+            old_tip,
+            new_tip: new_tip.to_string(),
+            depth,
+        });
+        tracing::warn!("Chain reorg of depth {} at fork height {}", depth, fork_height);
+    }
+
+    async fn evict_block(&self, height: u64, hash: &str) {
+        // Prefer the cached detail for the tx list, the node usually still has stale blocks
+        let txids: Vec<String> = match self.cache.blocks.get(hash).await {
+            Some(detail) => detail.transactions.iter().map(|t| t.txid.clone()).collect(),
+            None => match self.rpc.get_block(hash, 1).await {
+                Ok(block) => block.txids().unwrap_or_default(),
+                Err(_) => Vec::new(),
+            },
+        };
+
+        for txid in &txids {
+            self.cache.transactions.invalidate(txid).await;
+        }
+        self.cache.blocks.invalidate(hash).await;
+        self.cache.block_hash_by_height.invalidate(&height).await;
+    }
+}
+
+fn new_transaction_event(tx: &RpcTransaction) -> LiveEvent {
+    // Output addresses first, then input addresses when the node reports them
+    let mut seen = HashSet::new();
+    let addresses: Vec<String> = tx
+        .vout
+        .iter()
+        .filter_map(|o| o.script_pub_key.address.clone())
+        .chain(tx.vin.iter().filter_map(|i| i.address.clone()))
+        .filter(|addr| seen.insert(addr.clone()))
+        .collect();
+
+    LiveEvent::NewTransaction {
+        txid: tx.txid.clone(),
+        tx_type: tx.tx_type,
+        tx_type_label: tx_type_label(tx.tx_type),
+        total_output: tx.vout.iter().map(|o| o.value).sum(),
+        instantlock: tx.instantlock,
+        addresses,
+        pro_tx_hash: tx.pro_tx_hash(),
+    }
+}
+
+/// Next ZMQ notification, or pending forever when ZMQ isn't configured
+async fn next_notification(
+    rx: &mut Option<mpsc::Receiver<ZmqNotification>>,
+) -> Option<ZmqNotification> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
     }
 }
@@ -1,13 +1,22 @@
 mod api;
 mod cache;
+mod charts;
 mod config;
 mod error;
+mod geoip;
+mod index;
 mod live;
+mod masternodes;
+mod mempool;
 mod models;
+mod platform;
+mod pools;
 mod rpc;
+mod zmq;
 
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
 use tracing_subscriber::EnvFilter;
 
 pub use error::AppError;
@@ -18,6 +27,12 @@ pub struct AppState {
     pub cache: Arc<cache::AppCache>,
     pub live_tx: broadcast::Sender<live::LiveEvent>,
     pub config: Arc<config::Config>,
+    pub index: Option<Arc<index::AddressIndex>>,
+    pub charts: Option<Arc<charts::Charts>>,
+    pub pools: Arc<pools::PoolDb>,
+    pub geoip: Option<Arc<geoip::GeoIpDb>>,
+    pub platform: Arc<platform::Prober>,
+    pub socketio: Arc<api::socketio::SocketIoHub>,
 }
 
 #[tokio::main]
@@ -28,44 +43,147 @@ async fn main() {
         )
         .init();
 
-    let config = config::Config::from_env();
-    tracing::info!("Dashbook starting with RPC at {}", config.rpc.url);
+    let configs = match config::Config::load_networks() {
+        Ok(configs) => configs,
+        Err(e) => {
+            tracing::error!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    // Server settings are shared; they come from the first (default) network
+    let server = configs[0].server.clone();
+
+    let mut networks = Vec::with_capacity(configs.len());
+    for config in configs {
+        networks.push(start_network(config).await);
+    }
+
+    let app = api::build_router(networks, &server);
+
+    let bind_addr = &server.bind_address;
+    tracing::info!("Dashbook listening on {}", bind_addr);
+
+    let listener = tokio::net::TcpListener::bind(bind_addr).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .unwrap();
+}
 
-    let rpc_client = rpc::DashRpcClient::new(
-        config.rpc.url.clone(),
-        config.rpc.username.clone(),
-        config.rpc.password.clone(),
+/// Connect to one network's nodes and start its background tasks
+async fn start_network(config: config::Config) -> AppState {
+    let node_urls: Vec<&str> = config.rpc.nodes.iter().map(|n| n.url.as_str()).collect();
+    tracing::info!(
+        "Starting {} network with RPC at {}",
+        config.profile,
+        node_urls.join(", ")
     );
 
-    // Test RPC connection
+    let rpc_client = rpc::DashRpcClient::new(&config.rpc);
+
+    // Test RPC connections; unreachable nodes are retried by the health checker
+    rpc_client.check_health().await;
     match rpc_client.get_block_count().await {
-        Ok(height) => tracing::info!("Connected to Dash Core - block height: {}", height),
+        Ok(height) => tracing::info!(
+            "Connected to Dash Core ({}) - block height: {}",
+            config.profile,
+            height
+        ),
+        Err(e) => tracing::error!(
+            "No Dash Core RPC backend reachable yet for {}: {}",
+            config.profile,
+            e
+        ),
+    }
+
+    let rpc = Arc::new(rpc_client);
+    tokio::spawn(rpc.clone().run_health_checks());
+    let app_cache = Arc::new(cache::AppCache::new(&config.cache));
+    let (live_tx, _) = broadcast::channel(config.live.broadcast_capacity);
+    let socketio_hub = Arc::new(api::socketio::SocketIoHub::new());
+
+    let address_index = match config.index.path {
+        Some(ref path) => match index::AddressIndex::open(path) {
+            Ok(idx) => {
+                tracing::info!("Using local address index at {}", path);
+                Some(Arc::new(idx))
+            }
+            Err(e) => {
+                tracing::error!("Failed to open address index at {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let pool_db = match pools::PoolDb::load(config.pools.path.as_deref()) {
+        Ok(db) => Arc::new(db),
         Err(e) => {
-            tracing::error!("Failed to connect to Dash Core RPC: {}", e);
+            tracing::error!("Failed to load the pool database: {}", e);
             std::process::exit(1);
         }
-    }
+    };
 
-    let rpc = Arc::new(rpc_client);
-    let app_cache = Arc::new(cache::AppCache::new());
-    let (live_tx, _) = broadcast::channel(256);
-
-    let state = AppState {
-        rpc: rpc.clone(),
-        cache: app_cache.clone(),
-        live_tx: live_tx.clone(),
-        config: Arc::new(config.clone()),
+    let geoip_db = match config.geoip.path {
+        Some(ref path) => match geoip::GeoIpDb::load(path) {
+            Ok(db) => Some(Arc::new(db)),
+            Err(e) => {
+                tracing::error!("Failed to load the geoip table: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let prober = Arc::new(platform::Prober::new(&config.platform));
+
+    let chart_store = match config.charts.path {
+        Some(ref path) => match charts::Charts::open(path) {
+            Ok(store) => {
+                tracing::info!("Using chart store at {}", path);
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                tracing::error!("Failed to open chart store at {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
     };
 
     // Start background live updater
-    let updater = live::LiveUpdater::new(rpc.clone(), app_cache.clone(), live_tx.clone());
+    let mut updater = live::LiveUpdater::new(rpc.clone(), app_cache.clone(), live_tx.clone());
+    if let Some(ref zmq_url) = config.live.zmq_url {
+        let (zmq_tx, zmq_rx) = mpsc::channel(1024);
+        tokio::spawn(zmq::ZmqListener::new(zmq_url.clone(), zmq_tx).run());
+        updater = updater.with_zmq(zmq_rx);
+    }
     tokio::spawn(updater.run());
+    tokio::spawn(socketio_hub.clone().run(rpc.clone(), live_tx.subscribe()));
 
-    let app = api::build_router(state);
-
-    let bind_addr = &config.server.bind_address;
-    tracing::info!("Dashbook listening on {}", bind_addr);
+    if let Some(ref idx) = address_index {
+        let syncer = index::IndexSyncer::new(rpc.clone(), idx.clone());
+        tokio::spawn(syncer.run());
+        tokio::spawn(idx.clone().run_richlist(
+            Duration::from_secs(config.index.richlist_refresh_secs),
+            config.index.richlist_size,
+        ));
+    }
+    if let Some(ref store) = chart_store {
+        tokio::spawn(charts::ChartSyncer::new(rpc.clone(), store.clone()).run());
+    }
 
-    let listener = tokio::net::TcpListener::bind(bind_addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    AppState {
+        rpc,
+        cache: app_cache,
+        live_tx,
+        config: Arc::new(config),
+        index: address_index,
+        charts: chart_store,
+        pools: pool_db,
+        geoip: geoip_db,
+        platform: prober,
+        socketio: socketio_hub,
+    }
 }
@@ -0,0 +1,401 @@
+use futures::future::join_all;
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::sync::Arc;
+
+use super::{payments, states_at};
+use crate::models::masternode::{MasternodeEvent, MasternodeHistoryResponse};
+use crate::models::transaction::build_special_payload;
+use crate::rpc::types::RpcProtx;
+use crate::AppError;
+use crate::AppState;
+
+/// Blocks between state samples over the masternode's lifetime (about a day). A change
+/// that is undone within one step is missed, except a ban: the revival that ends it
+/// leaves `PoSeRevivedHeight` behind.
+const STATE_STEP: u64 = 576;
+/// Blocks between PoSe penalty samples
+const PENALTY_SAMPLE: u64 = 48;
+/// Recent blocks sampled for PoSe penalty increases (about a week)
+const PENALTY_WINDOW: u64 = 4032;
+/// Blocks before each ban sampled for the penalties that led to it (about two days)
+const BAN_LOOKBACK: u64 = 1152;
+/// Block headers per batch when looking up event times
+const HEADER_BATCH: usize = 200;
+/// Full blocks fetched at once when reading provider transactions
+const BLOCK_BATCH: usize = 8;
+
+/// Timeline of a masternode from its registration to the current tip.
+///
+/// The masternode's state is sampled with historic `protx info` at a fixed interval, and
+/// every step whose registration differs at its ends is narrowed down to the blocks where
+/// it changed by halving the range. Those blocks hold the provider transactions, bans
+/// and revivals. PoSe penalties decay by one per block, so a sampled penalty above the
+/// decayed earlier value means it was raised in between.
+pub async fn history(
+    state: &AppState,
+    pro_tx_hash: &str,
+) -> Result<Arc<MasternodeHistoryResponse>, AppError> {
+    if let Some(cached) = state.cache.masternode_history.get(pro_tx_hash).await {
+        return Ok(cached);
+    }
+
+    let (protx, tip) = tokio::join!(
+        state.rpc.get_protx_info(pro_tx_hash),
+        state.rpc.get_block_count(),
+    );
+    let (protx, tip) = (protx?, tip?);
+    let pro_tx_hash = protx.pro_tx_hash.as_str();
+    let registered = protx.state.registered_height;
+    let (changes, mut states) = lifetime_states(state, &protx, tip).await?;
+
+    // Sample penalties recently and ahead of every ban
+    let bans: BTreeSet<u64> = states
+        .values()
+        .filter(|s| s.state.pose_ban_height > 0)
+        .map(|s| s.state.pose_ban_height as u64)
+        .collect();
+    let mut windows = vec![(tip.saturating_sub(PENALTY_WINDOW).max(registered), tip)];
+    windows.extend(
+        bans.iter()
+            .map(|&ban| (ban.saturating_sub(BAN_LOOKBACK).max(registered), ban)),
+    );
+    let mut samples: BTreeSet<u64> = BTreeSet::new();
+    for (from, to) in windows {
+        samples.extend((from..=to).step_by(PENALTY_SAMPLE as usize));
+        samples.insert(to);
+    }
+    let samples: Vec<u64> = samples
+        .into_iter()
+        .filter(|h| !states.contains_key(h))
+        .collect();
+    states.extend(states_at(state, pro_tx_hash, &samples).await?);
+
+    let read = |height| state_at(state, pro_tx_hash, height);
+    let mut penalties: Vec<(u64, u32)> = Vec::new();
+    let known: Vec<(u64, RpcProtx)> = states.iter().map(|(h, s)| (*h, s.clone())).collect();
+    for pair in known.windows(2) {
+        let ((lo, before), (hi, after)) = (&pair[0], &pair[1]);
+        let (mut lo, hi) = (*lo, *hi);
+        // Banned masternodes keep their penalty instead of decaying
+        if before.state.pose_ban_height > 0 {
+            continue;
+        }
+        let mut penalty = before.state.pose_penalty as u64;
+        while lo < hi && after.state.pose_penalty as u64 > penalty.saturating_sub(hi - lo) {
+            let (from, base) = (lo, penalty);
+            let found = bisect(&read, from, (hi, after.clone()), |h, s| {
+                s.state.pose_penalty as u64 > base.saturating_sub(h - from)
+            })
+            .await?;
+            let Some((height, s)) = found else {
+                break;
+            };
+            penalties.push((height, s.state.pose_penalty));
+            if s.state.pose_ban_height > 0 {
+                break;
+            }
+            lo = height;
+            penalty = s.state.pose_penalty as u64;
+        }
+    }
+
+    let mut events: Vec<MasternodeEvent> = Vec::new();
+    let event = |height: u64, event: &str| MasternodeEvent {
+        height,
+        time: 0,
+        event: event.to_string(),
+        txid: None,
+        payload: None,
+        pose_penalty: None,
+        amount: None,
+    };
+
+    let heights: Vec<u64> = changes.iter().copied().collect();
+    let hashes = state.rpc.get_block_hashes(&heights).await?;
+    let mut blocks = Vec::with_capacity(hashes.len());
+    for chunk in hashes.chunks(BLOCK_BATCH) {
+        blocks.extend(join_all(chunk.iter().map(|hash| state.rpc.get_block(hash, 2))).await);
+    }
+    for (height, block) in heights.iter().zip(blocks) {
+        let block = block?;
+        let txs = block.transactions().ok_or_else(|| {
+            AppError::Internal(format!(
+                "Block {} at height {} has no decodable transactions",
+                block.hash, height
+            ))
+        })?;
+        for tx in txs {
+            if !(1..=4).contains(&tx.tx_type) || tx.pro_tx_hash().as_deref() != Some(pro_tx_hash) {
+                continue;
+            }
+            let Some(special) = build_special_payload(&tx) else {
+                continue;
+            };
+            let kind = special.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+            events.push(MasternodeEvent {
+                txid: Some(tx.txid.clone()),
+                payload: special.get("data").cloned(),
+                ..event(*height, kind)
+            });
+        }
+    }
+
+    let revivals: BTreeSet<u64> = states
+        .values()
+        .filter(|s| s.state.pose_revived_height > 0)
+        .map(|s| s.state.pose_revived_height as u64)
+        .collect();
+    events.extend(revivals.into_iter().map(|h| event(h, "PoSeRevived")));
+    events.extend(penalties.into_iter().map(|(h, penalty)| MasternodeEvent {
+        pose_penalty: Some(penalty),
+        ..event(h, "PoSePenalty")
+    }));
+    events.extend(bans.into_iter().map(|h| MasternodeEvent {
+        pose_penalty: states.get(&h).map(|s| s.state.pose_penalty),
+        ..event(h, "PoSeBan")
+    }));
+
+    let mut addresses: Vec<String> = states
+        .values()
+        .map(|s| s.state.payout_address.clone())
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    let (payments_available, payments_next_before) =
+        match payments(state, pro_tx_hash, &addresses, registered, tip).await {
+            Ok((payments, next_before)) => {
+                events.extend(payments.into_iter().map(|p| MasternodeEvent {
+                    txid: Some(p.txid),
+                    amount: Some(p.amount as f64 / 100_000_000.0),
+                    ..event(p.height, "Payment")
+                }));
+                (true, next_before)
+            }
+            // No -addressindex on the node and no local index
+            Err(e) if e.is_address_index_missing() => (false, None),
+            Err(e) => return Err(e),
+        };
+
+    let mut heights: Vec<u64> = events.iter().map(|e| e.height).collect();
+    heights.sort_unstable();
+    heights.dedup();
+    let mut times: BTreeMap<u64, u64> = BTreeMap::new();
+    for chunk in heights.chunks(HEADER_BATCH) {
+        let hashes = state.rpc.get_block_hashes(chunk).await?;
+        for header in state.rpc.get_block_headers(&hashes).await? {
+            times.insert(header.height, header.time);
+        }
+    }
+    for e in &mut events {
+        e.time = times.get(&e.height).copied().unwrap_or(0);
+    }
+    // Stable, so events in one block keep the order they were added in
+    events.sort_by_key(|e| std::cmp::Reverse(e.height));
+
+    let response = Arc::new(MasternodeHistoryResponse {
+        pro_tx_hash: pro_tx_hash.to_string(),
+        registered_height: registered,
+        height: tip,
+        payments_available,
+        payments_next_before,
+        events,
+    });
+    state
+        .cache
+        .masternode_history
+        .insert(pro_tx_hash.to_string(), response.clone())
+        .await;
+    Ok(response)
+}
+
+/// Heights where the masternode's registration changed, and its state at each of them,
+/// at registration and at `tip` (where `protx` is the current state).
+pub(crate) async fn lifetime_states(
+    state: &AppState,
+    protx: &RpcProtx,
+    tip: u64,
+) -> Result<(BTreeSet<u64>, BTreeMap<u64, RpcProtx>), AppError> {
+    let pro_tx_hash = protx.pro_tx_hash.as_str();
+    let registered = protx.state.registered_height;
+
+    let samples: Vec<u64> = (registered..tip).step_by(STATE_STEP as usize).collect();
+    let mut states: BTreeMap<u64, RpcProtx> = states_at(state, pro_tx_hash, &samples)
+        .await?
+        .into_iter()
+        .collect();
+    states.insert(tip, protx.clone());
+
+    let read = |height| state_at(state, pro_tx_hash, height);
+    let mut changes = transitions(&read, &mut states).await?;
+    changes.insert(registered);
+    Ok((changes, states))
+}
+
+/// Whether two states share a registration: the same service, keys, payout addresses,
+/// ban, revival and revocation. Penalties and payments move every block and don't count.
+fn same_registration(a: &RpcProtx, b: &RpcProtx) -> bool {
+    let (a, b) = (&a.state, &b.state);
+    a.service == b.service
+        && a.owner_address == b.owner_address
+        && a.voting_address == b.voting_address
+        && a.payout_address == b.payout_address
+        && a.operator_payout_address == b.operator_payout_address
+        && a.pub_key_operator == b.pub_key_operator
+        && a.pose_ban_height == b.pose_ban_height
+        && a.pose_revived_height == b.pose_revived_height
+        && a.revocation_reason == b.revocation_reason
+        && a.platform_node_id == b.platform_node_id
+        && a.platform_p2p_port == b.platform_p2p_port
+        && a.platform_http_port == b.platform_http_port
+}
+
+/// Heights between consecutive `states` at which the registration changed. Each change
+/// found is added to `states`, and the search goes on from it until the later sample's
+/// registration is reached, so a ban followed by a revival shows up as both.
+async fn transitions<R, F>(
+    read: &R,
+    states: &mut BTreeMap<u64, RpcProtx>,
+) -> Result<BTreeSet<u64>, AppError>
+where
+    R: Fn(u64) -> F,
+    F: Future<Output = Result<Option<RpcProtx>, AppError>>,
+{
+    let mut changes = BTreeSet::new();
+    let known: Vec<(u64, RpcProtx)> = states.iter().map(|(h, s)| (*h, s.clone())).collect();
+    for pair in known.windows(2) {
+        let ((lo, before), (hi, after)) = (&pair[0], &pair[1]);
+        let (mut lo, mut before) = (*lo, before.clone());
+        while !same_registration(&before, after) {
+            let found = bisect(read, lo, (*hi, after.clone()), |_, s| {
+                !same_registration(s, &before)
+            })
+            .await?;
+            let Some((height, s)) = found else {
+                break;
+            };
+            changes.insert(height);
+            states.insert(height, s.clone());
+            lo = height;
+            before = s;
+        }
+    }
+    Ok(changes)
+}
+
+/// The masternode's state at one height, None when the node can't answer for it
+async fn state_at(
+    state: &AppState,
+    pro_tx_hash: &str,
+    height: u64,
+) -> Result<Option<RpcProtx>, AppError> {
+    Ok(states_at(state, pro_tx_hash, &[height]).await?.remove(&height))
+}
+
+/// First height in `(lo, hi]` whose state matches `found`, given that the state at `hi`
+/// does and that a match holds until `hi` once it starts. None when a state along the way
+/// can't be read.
+async fn bisect<R, F>(
+    read: &R,
+    mut lo: u64,
+    (mut hi, mut hi_state): (u64, RpcProtx),
+    found: impl Fn(u64, &RpcProtx) -> bool,
+) -> Result<Option<(u64, RpcProtx)>, AppError>
+where
+    R: Fn(u64) -> F,
+    F: Future<Output = Result<Option<RpcProtx>, AppError>>,
+{
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        let Some(s) = read(mid).await? else {
+            return Ok(None);
+        };
+        if found(mid, &s) {
+            hi = mid;
+            hi_state = s;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(Some((hi, hi_state)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::transitions;
+    use crate::rpc::types::RpcProtx;
+    use crate::AppError;
+
+    /// Registered at 10, banned at 700 and revived at 705 with the same service and keys
+    fn timeline(height: u64) -> RpcProtx {
+        let (ban, revived) = match height {
+            700..705 => (700, -1),
+            705.. => (-1, 705),
+            _ => (-1, -1),
+        };
+        serde_json::from_value(serde_json::json!({
+            "type": "Regular",
+            "proTxHash": "ab".repeat(32),
+            "collateralHash": "cd".repeat(32),
+            "collateralIndex": 0,
+            "collateralAddress": "yCollateral",
+            "operatorReward": 0.0,
+            "confirmations": 100,
+            "state": {
+                "version": 2,
+                "service": "1.2.3.4:9999",
+                "registeredHeight": 10,
+                "lastPaidHeight": height.saturating_sub(3),
+                "consecutivePayments": 0,
+                "PoSePenalty": if ban > 0 { 90 } else { 0 },
+                "PoSeRevivedHeight": revived,
+                "PoSeBanHeight": ban,
+                "revocationReason": 0,
+                "ownerAddress": "yOwner",
+                "votingAddress": "yVoting",
+                "payoutAddress": "yPayout",
+                "pubKeyOperator": "operator",
+            },
+            "metaInfo": {
+                "lastDSQ": 0,
+                "mixingTxCount": 0,
+                "outboundAttemptCount": 0,
+                "lastOutboundAttempt": 0,
+                "lastOutboundAttemptElapsed": 0,
+                "lastOutboundSuccess": 0,
+                "lastOutboundSuccessElapsed": 0,
+            },
+        }))
+        .unwrap()
+    }
+
+    async fn read(height: u64) -> Result<Option<RpcProtx>, AppError> {
+        Ok(Some(timeline(height)))
+    }
+
+    #[tokio::test]
+    async fn finds_ban_undone_by_revival_within_one_step() {
+        // The samples around the ban share service and keys, and are never banned
+        let mut states: BTreeMap<u64, RpcProtx> =
+            [10, 586, 1162].into_iter().map(|h| (h, timeline(h))).collect();
+
+        let changes = transitions(&read, &mut states).await.unwrap();
+
+        assert_eq!(changes.into_iter().collect::<Vec<_>>(), vec![700, 705]);
+        assert_eq!(states[&700].state.pose_ban_height, 700);
+        assert_eq!(states[&705].state.pose_revived_height, 705);
+    }
+
+    #[tokio::test]
+    async fn unchanged_registration_reads_nothing() {
+        let mut states: BTreeMap<u64, RpcProtx> =
+            [10, 586].into_iter().map(|h| (h, timeline(h))).collect();
+        let unreachable = |_| async { panic!("no state should be read") };
+
+        let changes = transitions(&unreachable, &mut states).await.unwrap();
+
+        assert!(changes.is_empty());
+    }
+}
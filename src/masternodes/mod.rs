@@ -0,0 +1,158 @@
+pub mod history;
+pub mod payouts;
+pub mod queue;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::rpc::types::{RpcMasternodePayeeOutput, RpcMasternodePayment, RpcProtx};
+use crate::AppError;
+use crate::AppState;
+
+/// `protx info` and `masternode payments` calls per batch when reading past blocks
+const STATE_BATCH: usize = 100;
+/// Candidate blocks checked per payment scan, so shared payout addresses stay cheap
+const MAX_PAYMENT_CANDIDATES: usize = 2000;
+
+/// A block reward paid to a masternode
+pub(crate) struct Payment {
+    pub height: u64,
+    pub hash: String,
+    /// Coinbase transaction
+    pub txid: String,
+    /// Duffs paid to the owner and operator together
+    pub amount: i64,
+    /// Owner payout output first, then the operator's when it takes a share
+    pub payees: Vec<RpcMasternodePayeeOutput>,
+}
+
+/// Every registered masternode with its full state at the tip, for what `masternodelist`
+/// doesn't carry
+pub(crate) async fn protx_list(state: &AppState) -> Result<(u64, Arc<Vec<RpcProtx>>), AppError> {
+    let height = state.rpc.get_block_count().await?;
+    if let Some(cached) = state.cache.protx_list.get(&height).await {
+        return Ok((height, cached));
+    }
+
+    let list = Arc::new(state.rpc.get_protx_list(height).await?);
+    state.cache.protx_list.insert(height, list.clone()).await;
+    Ok((height, list))
+}
+
+/// A masternode's state as of each height. Heights the node can't answer for (before
+/// registration, or Core versions without historic `protx info`) are left out.
+pub(crate) async fn states_at(
+    state: &AppState,
+    pro_tx_hash: &str,
+    heights: &[u64],
+) -> Result<HashMap<u64, RpcProtx>, AppError> {
+    let mut states = HashMap::with_capacity(heights.len());
+    for chunk in heights.chunks(STATE_BATCH) {
+        let hashes = state.rpc.get_block_hashes(chunk).await?;
+        let infos = state.rpc.get_protx_info_at(pro_tx_hash, &hashes).await?;
+        for (height, info) in chunk.iter().zip(infos) {
+            if let Ok(info) = info {
+                states.insert(*height, info);
+            }
+        }
+    }
+    Ok(states)
+}
+
+/// Masternodes paid by the blocks at `heights`, with the blocks' hashes. Blocks the
+/// node can't report payees for come back as None.
+async fn block_payees(
+    state: &AppState,
+    heights: &[u64],
+) -> Result<Vec<(String, Option<Arc<Vec<RpcMasternodePayment>>>)>, AppError> {
+    let mut result = Vec::with_capacity(heights.len());
+    for chunk in heights.chunks(STATE_BATCH) {
+        let hashes = state.rpc.get_block_hashes(chunk).await?;
+        let mut payees = Vec::with_capacity(hashes.len());
+        let mut missing = Vec::new();
+        for hash in &hashes {
+            let cached = state.cache.block_payees.get(hash).await;
+            if cached.is_none() {
+                missing.push(hash.clone());
+            }
+            payees.push(cached);
+        }
+
+        let fetched = state.rpc.get_masternode_payments_at(&missing).await?;
+        let mut fetched: HashMap<String, Arc<Vec<RpcMasternodePayment>>> = missing
+            .into_iter()
+            .zip(fetched)
+            .filter_map(|(hash, blocks)| {
+                let block = blocks.ok()?.into_iter().find(|b| b.blockhash == hash)?;
+                Some((hash, Arc::new(block.masternodes)))
+            })
+            .collect();
+        for (hash, cached) in hashes.into_iter().zip(payees) {
+            let found = match cached {
+                Some(found) => Some(found),
+                None => {
+                    let found = fetched.remove(&hash);
+                    if let Some(ref found) = found {
+                        state.cache.block_payees.insert(hash.clone(), found.clone()).await;
+                    }
+                    found
+                }
+            };
+            result.push((hash, found));
+        }
+    }
+    Ok(result)
+}
+
+/// Blocks in `[from, to]` that paid the masternode through `addresses`, newest first.
+/// Coinbase outputs to the payout addresses are candidates, and the payees the node
+/// recorded for each block confirm which were this masternode's, since payout addresses
+/// can be shared. At most `MAX_PAYMENT_CANDIDATES` blocks are checked; the height below
+/// which older ones were left comes back alongside.
+pub(crate) async fn payments(
+    state: &AppState,
+    pro_tx_hash: &str,
+    addresses: &[String],
+    from: u64,
+    to: u64,
+) -> Result<(Vec<Payment>, Option<u64>), AppError> {
+    // txid by height
+    let mut candidates: HashMap<u64, String> = HashMap::new();
+    for address in addresses {
+        for delta in state.get_address_deltas(address, Some(from), Some(to)).await? {
+            let in_range = (from..=to).contains(&delta.height);
+            if delta.blockindex == 0 && delta.satoshis > 0 && in_range {
+                candidates.insert(delta.height, delta.txid);
+            }
+        }
+    }
+
+    let mut heights: Vec<u64> = candidates.keys().copied().collect();
+    heights.sort_unstable_by(|a, b| b.cmp(a));
+    let next_before = if heights.len() > MAX_PAYMENT_CANDIDATES {
+        heights.truncate(MAX_PAYMENT_CANDIDATES);
+        heights.last().copied()
+    } else {
+        None
+    };
+
+    let payees = block_payees(state, &heights).await?;
+    let payments = heights
+        .into_iter()
+        .zip(payees)
+        .filter_map(|(height, (hash, paid))| {
+            let paid = paid?
+                .iter()
+                .find(|mn| mn.pro_tx_hash == pro_tx_hash)?
+                .clone();
+            Some(Payment {
+                height,
+                hash,
+                txid: candidates.remove(&height)?,
+                amount: paid.amount,
+                payees: paid.payees,
+            })
+        })
+        .collect();
+    Ok((payments, next_before))
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use super::history::lifetime_states;
+use super::payments;
+use crate::models::masternode::{MasternodePayment, MasternodePaymentScan};
+use crate::rpc::types::RpcProtx;
+use crate::AppError;
+use crate::AppState;
+
+/// Payment block headers fetched per batch
+const HEADER_BATCH: usize = 100;
+
+/// Payments to every payout address the masternode has had below `before` (the tip when
+/// None), newest first. The owner and operator shares are the outputs the node recorded
+/// for the block; without an operator share the owner is paid the whole reward.
+pub async fn payouts(
+    state: &AppState,
+    protx: &RpcProtx,
+    before: Option<u64>,
+) -> Result<Arc<MasternodePaymentScan>, AppError> {
+    let key = format!("{}:{:?}", protx.pro_tx_hash, before);
+    if let Some(cached) = state.cache.masternode_payments.get(&key).await {
+        return Ok(cached);
+    }
+
+    let tip = state.rpc.get_block_count().await?;
+    let to = before.map_or(tip, |b| b.saturating_sub(1).min(tip));
+    // Every payout address the masternode has had, so payments made before a
+    // ProUpRegTx changed it are included
+    let (_, states) = lifetime_states(state, protx, tip).await?;
+    let mut addresses: Vec<String> = states
+        .values()
+        .map(|s| s.state.payout_address.clone())
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    let (found, next_before) = if to >= protx.state.registered_height {
+        payments(
+            state,
+            &protx.pro_tx_hash,
+            &addresses,
+            protx.state.registered_height,
+            to,
+        )
+        .await?
+    } else {
+        (Vec::new(), None)
+    };
+
+    let mut result = Vec::with_capacity(found.len());
+    for chunk in found.chunks(HEADER_BATCH) {
+        let hashes: Vec<String> = chunk.iter().map(|p| p.hash.clone()).collect();
+        let headers = state.rpc.get_block_headers(&hashes).await?;
+
+        for (payment, header) in chunk.iter().zip(headers) {
+            let Some((owner, operators)) = payment.payees.split_first() else {
+                continue;
+            };
+            let operator: i64 = operators.iter().map(|o| o.amount).sum();
+            result.push(MasternodePayment {
+                height: payment.height,
+                time: header.time,
+                txid: payment.txid.clone(),
+                amount: payment.amount as f64 / 100_000_000.0,
+                owner_amount: owner.amount as f64 / 100_000_000.0,
+                operator_amount: operator as f64 / 100_000_000.0,
+                payout_address: owner.address.clone(),
+                operator_payout_address: operators.first().map(|o| o.address.clone()),
+            });
+        }
+    }
+
+    let result = Arc::new(MasternodePaymentScan {
+        payments: result,
+        next_before,
+    });
+    state
+        .cache
+        .masternode_payments
+        .insert(key, result.clone())
+        .await;
+    Ok(result)
+}
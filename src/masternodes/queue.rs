@@ -0,0 +1,131 @@
+use std::cmp::Ordering;
+
+use super::protx_list;
+use crate::models::masternode::{PaymentQueueEntry, PaymentQueueResponse};
+use crate::rpc::types::{RpcBlockchainInfo, RpcProtx};
+use crate::AppError;
+use crate::AppState;
+
+/// Blocks in a row an Evo node is paid for before `mn_rr` activates
+const EVO_WEIGHT: u32 = 4;
+/// Block target spacing in seconds
+const BLOCK_SECS: u64 = 150;
+
+/// The next `count` payees after the tip
+pub async fn next_payees(state: &AppState, count: usize) -> Result<PaymentQueueResponse, AppError> {
+    let ((height, list), chain) =
+        tokio::try_join!(protx_list(state), state.rpc.get_blockchain_info())?;
+    let weighted = evo_consecutive_payments(state, &chain).await?;
+
+    let queue = projected_payees(&list, height, weighted);
+    let valid = list.iter().filter(|mn| is_valid(mn)).count();
+    let payees = queue
+        .iter()
+        .take(count)
+        .enumerate()
+        .map(|(i, mn)| PaymentQueueEntry {
+            position: i as u32 + 1,
+            height: height + i as u64 + 1,
+            estimated_time: chain.time + (i as u64 + 1) * BLOCK_SECS,
+            pro_tx_hash: mn.pro_tx_hash.clone(),
+            mn_type: mn.mn_type.clone(),
+            service: mn.state.service.clone(),
+            payout_address: mn.state.payout_address.clone(),
+            last_paid_height: mn.state.last_paid_height,
+            registered_height: mn.state.registered_height,
+        })
+        .collect();
+
+    Ok(PaymentQueueResponse {
+        height,
+        valid,
+        cycle_blocks: queue.len(),
+        evo_consecutive_payments: weighted,
+        payees,
+    })
+}
+
+/// Whether Evo nodes still get consecutive payments, i.e. `mn_rr` isn't active. Core
+/// versions that don't know the deployment predate it.
+async fn evo_consecutive_payments(
+    state: &AppState,
+    chain: &RpcBlockchainInfo,
+) -> Result<bool, AppError> {
+    if let Some(deployment) = chain.softforks.get("mn_rr") {
+        return Ok(!deployment.active);
+    }
+    match state.rpc.get_deployment_info().await {
+        Ok(info) => Ok(!info.deployments.get("mn_rr").is_some_and(|d| d.active)),
+        Err(AppError::Rpc { .. }) => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
+fn is_valid(mn: &RpcProtx) -> bool {
+    mn.state.pose_ban_height < 0
+}
+
+fn is_evo(mn: &RpcProtx) -> bool {
+    mn.mn_type == "Evo"
+}
+
+/// Height a masternode waits from, as in Core's `CompareByLastPaid`
+fn paid_from(mn: &RpcProtx) -> u64 {
+    let s = &mn.state;
+    if s.pose_revived_height > s.last_paid_height as i64 {
+        s.pose_revived_height as u64
+    } else if s.last_paid_height == 0 {
+        s.registered_height
+    } else {
+        s.last_paid_height
+    }
+}
+
+/// Core orders ties by proTxHash as a little-endian number, so by its hex bytes reversed
+fn compare_by_last_paid(a: &RpcProtx, b: &RpcProtx) -> Ordering {
+    paid_from(a).cmp(&paid_from(b)).then_with(|| {
+        let a = a.pro_tx_hash.as_bytes().rchunks(2);
+        let b = b.pro_tx_hash.as_bytes().rchunks(2);
+        a.cmp(b)
+    })
+}
+
+/// One full payment cycle after `height`, block by block, following Core's
+/// `GetProjectedMNPayees`. With consecutive payments an Evo node takes four blocks in a
+/// row, and one that is part way through its run at the tip finishes it first.
+fn projected_payees(list: &[RpcProtx], height: u64, weighted: bool) -> Vec<&RpcProtx> {
+    let weight = |mn: &RpcProtx| {
+        if weighted && is_evo(mn) {
+            EVO_WEIGHT
+        } else {
+            1
+        }
+    };
+    let current = list.iter().find(|mn| {
+        weighted
+            && is_valid(mn)
+            && is_evo(mn)
+            && mn.state.last_paid_height == height
+            && mn.state.consecutive_payments < EVO_WEIGHT
+    });
+    let remaining = current.map_or(0, |mn| EVO_WEIGHT - mn.state.consecutive_payments);
+
+    let mut rest: Vec<&RpcProtx> = list
+        .iter()
+        .filter(|mn| is_valid(mn) && current.is_none_or(|c| c.pro_tx_hash != mn.pro_tx_hash))
+        .collect();
+    rest.sort_by(|a, b| compare_by_last_paid(a, b));
+
+    let mut queue: Vec<&RpcProtx> = Vec::new();
+    if let Some(mn) = current {
+        queue.extend((0..remaining).map(|_| mn));
+    }
+    for mn in rest {
+        queue.extend((0..weight(mn)).map(|_| mn));
+    }
+    // The blocks it was already paid come around again at the end of the cycle
+    if let Some(mn) = current {
+        queue.extend((remaining..EVO_WEIGHT).map(|_| mn));
+    }
+    queue
+}
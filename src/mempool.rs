@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::rpc::types::RpcTransaction;
+use crate::AppError;
+use crate::AppState;
+
+/// `getrawtransaction` calls per batch while decoding new mempool transactions
+const DECODE_BATCH: usize = 200;
+
+/// Decoded mempool transactions by txid. Each one is fetched once and then served from
+/// `cache.mempool_txs`; transactions mined or evicted meanwhile are left out.
+pub async fn transactions(
+    state: &AppState,
+    txids: &[String],
+) -> Result<HashMap<String, Arc<RpcTransaction>>, AppError> {
+    let mut txs = HashMap::with_capacity(txids.len());
+    let mut missing: Vec<String> = Vec::new();
+    for txid in txids {
+        match state.cache.mempool_txs.get(txid).await {
+            Some(tx) => {
+                txs.insert(txid.clone(), tx);
+            }
+            None => missing.push(txid.clone()),
+        }
+    }
+
+    for chunk in missing.chunks(DECODE_BATCH) {
+        let fetched = state.rpc.get_raw_transactions(chunk).await?;
+        for (txid, tx) in chunk.iter().zip(fetched) {
+            let Ok(tx) = tx else {
+                continue;
+            };
+            if tx.blockhash.is_some() {
+                continue;
+            }
+            let tx = Arc::new(tx);
+            state.cache.mempool_txs.insert(txid.clone(), tx.clone()).await;
+            txs.insert(txid.clone(), tx);
+        }
+    }
+
+    Ok(txs)
+}
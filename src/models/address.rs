@@ -30,3 +30,39 @@ pub struct AddressUtxo {
     pub value: f64,
     pub height: u64,
 }
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RichListEntry {
+    pub rank: usize,
+    pub address: String,
+    pub balance: f64,
+    pub balance_sat: i64,
+    /// Percent of all coins held by indexed addresses
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DistributionBucket {
+    /// Lower bound in DASH (inclusive)
+    pub min: f64,
+    /// Upper bound in DASH (exclusive); None for the last bucket
+    pub max: Option<f64>,
+    pub addresses: u64,
+    pub balance: f64,
+    pub balance_sat: i64,
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RichListResponse {
+    /// Index height the list was computed at
+    pub height: u64,
+    pub updated_at: u64,
+    /// Addresses with a non-zero balance
+    pub total_addresses: u64,
+    pub total_balance: f64,
+    pub page: u32,
+    pub pages: u32,
+    pub addresses: Vec<RichListEntry>,
+    pub distribution: Vec<DistributionBucket>,
+}
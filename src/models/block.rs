@@ -1,7 +1,8 @@
 use serde::Serialize;
 
+use super::pool::Miner;
 use super::transaction::TransactionSummary;
-use crate::rpc::types::RpcBlock;
+use crate::rpc::types::{RpcBlock, RpcBlockStats};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct BlockSummary {
@@ -13,6 +14,9 @@ pub struct BlockSummary {
     pub difficulty: f64,
     pub chainlock: bool,
     pub credit_pool_balance: Option<f64>,
+    /// None when the node can't compute block stats (e.g. without `-txindex`)
+    pub stats: Option<BlockStats>,
+    pub miner: Option<Miner>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -34,9 +38,66 @@ pub struct BlockDetail {
     pub next_block_hash: Option<String>,
     pub chainlock: bool,
     pub cb_tx: Option<CbTxInfo>,
+    pub stats: Option<BlockStats>,
+    pub reward: Option<BlockReward>,
+    pub miner: Option<Miner>,
+    /// Transactions per type over the whole block
+    pub tx_types: Vec<TxTypeCount>,
+    /// Transactions matching the `type` filter
+    pub tx_total: usize,
+    pub page: u32,
+    pub pages: u32,
     pub transactions: Vec<TransactionSummary>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct TxTypeCount {
+    pub tx_type: u32,
+    pub tx_type_label: String,
+    pub count: usize,
+}
+
+/// Totals from `getblockstats`, amounts in DASH
+#[derive(Debug, Serialize, Clone)]
+pub struct BlockStats {
+    pub total_fee: f64,
+    /// Average fee rate in duffs per byte
+    pub avg_fee_rate: f64,
+    /// 10th, 25th, 50th, 75th and 90th percentile fee rates in duffs per byte
+    pub fee_rate_percentiles: Vec<f64>,
+    pub subsidy: f64,
+    pub inputs: u32,
+    pub outputs: u32,
+    pub total_out: f64,
+    /// Change in the number of unspent outputs
+    pub utxo_increase: i32,
+}
+
+/// How the coinbase was paid out, amounts in DASH
+#[derive(Debug, Serialize, Clone)]
+pub struct BlockReward {
+    pub total: f64,
+    /// First coinbase output
+    pub miner: f64,
+    /// Output to the paid masternode's payout address
+    pub masternode: f64,
+    /// Output to the paid masternode's operator payout address
+    pub operator: f64,
+    /// Everything else: superblock payments, or the masternode payment when the node
+    /// can't tell who was paid
+    pub other: f64,
+    pub masternode_payee: Option<MasternodePayee>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MasternodePayee {
+    pub pro_tx_hash: String,
+    pub address: String,
+    pub amount: f64,
+    pub operator_address: Option<String>,
+    pub operator_amount: f64,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct CbTxInfo {
     pub version: u32,
@@ -59,6 +120,89 @@ impl BlockSummary {
             difficulty: block.difficulty,
             chainlock: block.chainlock,
             credit_pool_balance: block.cb_tx.as_ref().map(|cb| cb.credit_pool_balance),
+            stats: None,
+            miner: None,
+        }
+    }
+}
+
+impl BlockDetail {
+    /// One page of the transactions accepted by `filter`, from a detail holding all of them
+    pub fn page(
+        &self,
+        filter: impl Fn(&TransactionSummary) -> bool,
+        page: u32,
+        limit: u32,
+    ) -> Self {
+        let matched: Vec<&TransactionSummary> =
+            self.transactions.iter().filter(|tx| filter(tx)).collect();
+        let transactions = matched
+            .iter()
+            .skip((page.max(1) as usize - 1).saturating_mul(limit as usize))
+            .take(limit as usize)
+            .map(|tx| (*tx).clone())
+            .collect();
+
+        Self {
+            hash: self.hash.clone(),
+            height: self.height,
+            version: self.version,
+            merkle_root: self.merkle_root.clone(),
+            time: self.time,
+            median_time: self.median_time,
+            nonce: self.nonce,
+            bits: self.bits.clone(),
+            difficulty: self.difficulty,
+            chainwork: self.chainwork.clone(),
+            n_tx: self.n_tx,
+            confirmations: self.confirmations,
+            size: self.size,
+            previous_block_hash: self.previous_block_hash.clone(),
+            next_block_hash: self.next_block_hash.clone(),
+            chainlock: self.chainlock,
+            cb_tx: self.cb_tx.clone(),
+            stats: self.stats.clone(),
+            reward: self.reward.clone(),
+            miner: self.miner.clone(),
+            tx_types: self.tx_types.clone(),
+            tx_total: matched.len(),
+            page,
+            pages: (matched.len() as u32).div_ceil(limit),
+            transactions,
+        }
+    }
+}
+
+impl TxTypeCount {
+    /// Tally (type, label) pairs, in type order
+    pub fn count<'a>(types: impl IntoIterator<Item = (u32, &'a str)>) -> Vec<Self> {
+        let mut counts: Vec<Self> = Vec::new();
+        for (tx_type, label) in types {
+            match counts.iter_mut().find(|c| c.tx_type == tx_type) {
+                Some(c) => c.count += 1,
+                None => counts.push(Self {
+                    tx_type,
+                    tx_type_label: label.to_string(),
+                    count: 1,
+                }),
+            }
+        }
+        counts.sort_by_key(|c| c.tx_type);
+        counts
+    }
+}
+
+impl BlockStats {
+    pub fn from_rpc(stats: &RpcBlockStats) -> Self {
+        Self {
+            total_fee: stats.totalfee / 1e8,
+            avg_fee_rate: stats.avgfeerate,
+            fee_rate_percentiles: stats.feerate_percentiles.clone(),
+            subsidy: stats.subsidy as f64 / 1e8,
+            inputs: stats.ins,
+            outputs: stats.outs,
+            total_out: stats.total_out as f64 / 1e8,
+            utxo_increase: stats.utxo_increase,
         }
     }
 }
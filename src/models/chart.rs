@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ChartPoint {
+    /// Start of the interval (block time for `interval=block`)
+    pub time: u64,
+    /// Last block height in the interval
+    pub height: u64,
+    pub blocks: u32,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ChartResponse {
+    pub metric: String,
+    pub unit: String,
+    pub interval: String,
+    pub from: u64,
+    pub to: u64,
+    /// Highest block with computed stats; charts lag the chain while catching up
+    pub synced_height: Option<u64>,
+    pub points: Vec<ChartPoint>,
+}
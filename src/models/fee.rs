@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+/// Fee rates in duffs per byte
+#[derive(Debug, Serialize, Clone)]
+pub struct FeeEstimate {
+    /// Confirmation target in blocks
+    pub target: u32,
+    /// `estimatesmartfee` results; None when the node has too little data
+    pub conservative: Option<f64>,
+    pub economical: Option<f64>,
+    /// Suggested rate: the economical estimate, else the conservative one, else a fallback
+    pub fee_rate: f64,
+    /// Where `fee_rate` came from: `node`, `mempool`, `blocks` or `min_relay`
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FeesResponse {
+    pub height: u64,
+    /// Fee rates in duffs per byte
+    pub min_relay_fee: f64,
+    pub mempool_min_fee: f64,
+    /// Median fee rate paid in recent blocks with fee-paying transactions
+    pub recent_blocks_fee_rate: Option<f64>,
+    pub mempool_bytes: u64,
+    pub estimates: Vec<FeeEstimate>,
+}
@@ -1,5 +1,7 @@
 use serde::Serialize;
+use serde_json::Value;
 
+use super::quorum::QuorumMembership;
 use crate::rpc::types::{RpcMasternodeListEntry, RpcProtx};
 
 #[derive(Debug, Serialize, Clone)]
@@ -12,11 +14,17 @@ pub struct MasternodeSummary {
     pub last_paid_block: u64,
     pub last_paid_time: u64,
     pub registered_height: Option<u64>,
+    pub consecutive_payments: u32,
     pub collateral_address: String,
     pub payout_address: String,
+    pub owner_address: String,
+    pub voting_address: String,
+    /// Percentage of each payment the operator is due
+    pub operator_reward: Option<f64>,
     pub platform_node_id: Option<String>,
     pub platform_http_port: Option<u16>,
     pub platform_p2p_port: Option<u16>,
+    pub is_platform_banned: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -46,6 +54,158 @@ pub struct MasternodeDetail {
     pub confirmations: i64,
     pub last_dsq: i64,
     pub mixing_tx_count: u32,
+    /// Active quorums the masternode is a member of
+    pub quorums: Vec<QuorumMembership>,
+}
+
+/// One entry of a masternode's timeline
+#[derive(Debug, Serialize, Clone)]
+pub struct MasternodeEvent {
+    pub height: u64,
+    pub time: u64,
+    /// `ProRegTx`, `ProUpServTx`, `ProUpRegTx`, `ProUpRevTx`, `PoSePenalty`, `PoSeBan`,
+    /// `PoSeRevived` or `Payment`
+    pub event: String,
+    pub txid: Option<String>,
+    /// Provider transaction payload, as in `/api/tx/{txid}`
+    pub payload: Option<Value>,
+    /// PoSe penalty right after the event
+    pub pose_penalty: Option<u32>,
+    /// Paid to the payout address, in DASH
+    pub amount: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MasternodeHistoryResponse {
+    pub pro_tx_hash: String,
+    pub registered_height: u64,
+    /// Chain height the history was built at
+    pub height: u64,
+    /// False when payout addresses can't be looked up (no address index)
+    pub payments_available: bool,
+    /// Set when payments below this height weren't scanned; `/payments?before=` has them
+    pub payments_next_before: Option<u64>,
+    /// Newest first
+    pub events: Vec<MasternodeEvent>,
+}
+
+/// One block of the projected payment queue
+#[derive(Debug, Serialize, Clone)]
+pub struct PaymentQueueEntry {
+    /// 1 is the next block
+    pub position: u32,
+    pub height: u64,
+    /// From the tip's time and the 2.5 minute block target
+    pub estimated_time: u64,
+    pub pro_tx_hash: String,
+    pub mn_type: String,
+    pub service: String,
+    pub payout_address: String,
+    pub last_paid_height: u64,
+    pub registered_height: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PaymentQueueResponse {
+    pub height: u64,
+    /// Masternodes eligible for payment (not PoSe banned)
+    pub valid: usize,
+    /// Blocks until every valid masternode has been paid once more
+    pub cycle_blocks: usize,
+    /// Whether Evo nodes are paid four blocks in a row (before `mn_rr` activates)
+    pub evo_consecutive_payments: bool,
+    pub payees: Vec<PaymentQueueEntry>,
+}
+
+/// A block reward paid to a masternode, split between owner and operator
+#[derive(Debug, Serialize, Clone)]
+pub struct MasternodePayment {
+    pub height: u64,
+    pub time: u64,
+    /// Coinbase transaction
+    pub txid: String,
+    /// Owner and operator shares together, in DASH
+    pub amount: f64,
+    pub owner_amount: f64,
+    pub operator_amount: f64,
+    pub payout_address: String,
+    pub operator_payout_address: Option<String>,
+}
+
+/// Payments found by one bounded scan of a masternode's payout addresses
+#[derive(Debug, Clone)]
+pub struct MasternodePaymentScan {
+    /// Newest first
+    pub payments: Vec<MasternodePayment>,
+    /// Set when older blocks were left unscanned; the next scan goes below it
+    pub next_before: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MasternodePaymentsResponse {
+    pub pro_tx_hash: String,
+    pub payout_address: String,
+    /// Percentage of each payment the operator is due
+    pub operator_reward: f64,
+    pub total: usize,
+    /// All payments together, in DASH
+    pub total_amount: f64,
+    pub page: u32,
+    pub pages: u32,
+    /// Newest first
+    pub payments: Vec<MasternodePayment>,
+    /// Set when older blocks weren't scanned; pass it as `before` for earlier payments
+    pub next_before: Option<u64>,
+}
+
+/// Masternodes sharing one value of a breakdown
+#[derive(Debug, Serialize, Clone)]
+pub struct MasternodeCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Masternodes with a PoSe penalty in `[min, max)`
+#[derive(Debug, Serialize, Clone)]
+pub struct PoseBucket {
+    pub min: u32,
+    pub max: Option<u32>,
+    pub count: usize,
+    pub regular: usize,
+    pub evo: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VersionCount {
+    pub protocol_version: u32,
+    pub user_agent: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AsnCount {
+    /// None for reserved ranges and addresses not in the table
+    pub asn: Option<u32>,
+    pub name: Option<String>,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MasternodeStatsResponse {
+    pub total: usize,
+    pub by_status: Vec<MasternodeCount>,
+    pub by_type: Vec<MasternodeCount>,
+    /// Penalty above which a masternode is banned: the number of valid masternodes
+    pub pose_max_penalty: u32,
+    pub pose_histogram: Vec<PoseBucket>,
+    /// Masternodes connected to the node, whose version is known
+    pub versions_known: usize,
+    pub versions: Vec<VersionCount>,
+    /// Masternodes whose IP is in the geoip table; the geoip fields are None without one
+    pub located: Option<usize>,
+    /// By ISO 3166 country code; `unknown` when not located
+    pub countries: Option<Vec<MasternodeCount>>,
+    pub asns: Option<Vec<AsnCount>>,
 }
 
 impl MasternodeSummary {
@@ -59,11 +219,32 @@ impl MasternodeSummary {
             last_paid_block: entry.lastpaidblock,
             last_paid_time: entry.lastpaidtime,
             registered_height: None,
+            consecutive_payments: entry.consecutive_payments,
             collateral_address: entry.collateraladdress.clone(),
             payout_address: entry.payee.clone(),
+            owner_address: entry.owneraddress.clone(),
+            voting_address: entry.votingaddress.clone(),
+            operator_reward: None,
             platform_node_id: entry.platform_node_id.clone(),
             platform_http_port: entry.platform_http_port,
             platform_p2p_port: entry.platform_p2p_port,
+            is_platform_banned: None,
+        }
+    }
+
+    /// Fill in what `masternodelist` doesn't carry from the masternode's `protx list` entry
+    pub fn merge_protx(&mut self, protx: &RpcProtx) {
+        self.registered_height = Some(protx.state.registered_height);
+        self.operator_reward = Some(protx.operator_reward);
+        self.is_platform_banned = protx.meta_info.is_platform_banned;
+        if self.platform_node_id.is_none() {
+            self.platform_node_id = protx.state.platform_node_id.clone();
+        }
+        if self.platform_http_port.is_none() {
+            self.platform_http_port = protx.state.platform_http_port;
+        }
+        if self.platform_p2p_port.is_none() {
+            self.platform_p2p_port = protx.state.platform_p2p_port;
         }
     }
 }
@@ -96,6 +277,7 @@ impl MasternodeDetail {
             confirmations: protx.confirmations,
             last_dsq: protx.meta_info.last_dsq,
             mixing_tx_count: protx.meta_info.mixing_tx_count,
+            quorums: Vec::new(),
         }
     }
 }
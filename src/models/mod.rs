@@ -1,6 +1,11 @@
 pub mod address;
 pub mod block;
+pub mod chart;
+pub mod fee;
 pub mod governance;
 pub mod masternode;
 pub mod network;
+pub mod platform;
+pub mod pool;
+pub mod quorum;
 pub mod transaction;
@@ -1,5 +1,7 @@
 use serde::Serialize;
 
+use super::block::TxTypeCount;
+
 #[derive(Debug, Serialize, Clone)]
 pub struct NetworkOverview {
     pub chain: String,
@@ -19,6 +21,20 @@ pub struct NetworkOverview {
     pub connections_mn: u32,
     pub credit_pool_balance: f64,
     pub masternode_count: MasternodeCountInfo,
+    pub rpc_backends: Vec<RpcBackendStatus>,
+}
+
+/// Health of one Dash Core RPC backend
+#[derive(Debug, Serialize, Clone)]
+pub struct RpcBackendStatus {
+    pub url: String,
+    /// Backend currently receiving requests
+    pub active: bool,
+    pub in_rotation: bool,
+    pub height: Option<u64>,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+    pub checked_at: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -44,3 +60,73 @@ pub struct StatusResponse {
     pub tx_rate: f64,
     pub chain: String,
 }
+
+#[derive(Debug, Serialize, Clone)]
+pub struct NetworkListResponse {
+    pub default: String,
+    pub networks: Vec<NetworkSummary>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct NetworkSummary {
+    pub name: String,
+    pub default: bool,
+    /// None while none of the network's nodes answer
+    pub block_height: Option<u64>,
+    pub api: String,
+    pub insight_api: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MempoolResponse {
+    pub size: u64,
+    pub bytes: u64,
+    pub total_fee: f64,
+    pub min_fee: f64,
+    pub instantsend_locks: u64,
+    /// Entries with and without an InstantSend lock
+    pub locked: usize,
+    pub unlocked: usize,
+    /// Bounds in duffs per byte
+    pub fee_histogram: Vec<MempoolBucket>,
+    /// Bounds in bytes
+    pub size_histogram: Vec<MempoolBucket>,
+    /// Bounds in seconds since the transaction entered the mempool
+    pub age_histogram: Vec<MempoolBucket>,
+    pub tx_types: Vec<TxTypeCount>,
+    pub page: u32,
+    pub pages: u32,
+    pub entries: Vec<MempoolEntry>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MempoolBucket {
+    /// Lower bound (inclusive)
+    pub min: f64,
+    /// Upper bound (exclusive); None for the last bucket
+    pub max: Option<f64>,
+    pub count: u64,
+    pub bytes: u64,
+    pub fees: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MempoolEntry {
+    pub txid: String,
+    pub tx_type: u32,
+    pub tx_type_label: String,
+    pub size: u64,
+    pub fee: f64,
+    /// Duffs per byte
+    pub fee_rate: f64,
+    pub time: u64,
+    /// Chain height when the transaction entered the mempool
+    pub height: u64,
+    /// Unconfirmed ancestors and descendants, including the transaction itself
+    pub ancestors: u64,
+    pub descendants: u64,
+    /// Unconfirmed parents
+    pub depends: Vec<String>,
+    pub instantlock: bool,
+    pub total_output: f64,
+}
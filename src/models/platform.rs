@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+use crate::rpc::types::RpcProtx;
+
+/// Result of connecting to a Platform HTTP port
+#[derive(Debug, Serialize, Clone)]
+pub struct PlatformProbe {
+    pub reachable: bool,
+    /// Time to connect
+    pub latency_ms: Option<u64>,
+    /// When the connection was tried, as a unix timestamp
+    pub checked_at: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PlatformNode {
+    pub pro_tx_hash: String,
+    /// `ENABLED` or `POSE_BANNED`
+    pub status: String,
+    pub service: String,
+    pub platform_node_id: Option<String>,
+    pub platform_p2p_port: Option<u16>,
+    pub platform_http_port: Option<u16>,
+    /// Where the Platform HTTP API is served: the announced address, or the service IP
+    /// with `platform_http_port`
+    pub platform_http_address: Option<String>,
+    pub is_platform_banned: Option<bool>,
+    pub platform_ban_height_updated: Option<u64>,
+    pub pose_penalty: u32,
+    pub registered_height: u64,
+    pub last_paid_height: u64,
+    /// Only with `probe=true`; None when the probe budget ran out
+    pub probe: Option<PlatformProbe>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PlatformNodesResponse {
+    pub height: u64,
+    pub total: usize,
+    pub enabled: usize,
+    pub platform_banned: usize,
+    pub probed: usize,
+    pub reachable: usize,
+    pub nodes: Vec<PlatformNode>,
+}
+
+impl PlatformNode {
+    pub fn from_protx(protx: &RpcProtx) -> Self {
+        let s = &protx.state;
+        let announced = s
+            .addresses
+            .as_ref()
+            .and_then(|a| a.platform_https.as_ref())
+            .and_then(|a| a.first())
+            .cloned();
+        let platform_http_address = announced.or_else(|| {
+            let port = s.platform_http_port?;
+            let (host, _) = s.service.rsplit_once(':')?;
+            Some(format!("{}:{}", host, port))
+        });
+
+        Self {
+            pro_tx_hash: protx.pro_tx_hash.clone(),
+            status: if s.pose_ban_height < 0 {
+                "ENABLED".to_string()
+            } else {
+                "POSE_BANNED".to_string()
+            },
+            service: s.service.clone(),
+            platform_node_id: s.platform_node_id.clone(),
+            platform_p2p_port: s.platform_p2p_port,
+            platform_http_port: s.platform_http_port,
+            platform_http_address,
+            is_platform_banned: protx.meta_info.is_platform_banned,
+            platform_ban_height_updated: protx.meta_info.platform_ban_height_updated,
+            pose_penalty: s.pose_penalty,
+            registered_height: s.registered_height,
+            last_paid_height: s.last_paid_height,
+            probe: None,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Serialize;
+
+/// Who mined a block
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Miner {
+    /// None when neither the coinbase tag nor the payout address is in the pool database
+    pub pool: Option<String>,
+    pub link: Option<String>,
+    /// Address of the first coinbase output
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PoolShare {
+    /// "Unknown" groups blocks from unidentified miners
+    pub name: String,
+    pub link: Option<String>,
+    pub blocks: u32,
+    /// Percent of blocks in the window
+    pub share: f64,
+    /// Estimated hashes per second, from the share and the network hashrate
+    pub hashrate: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PoolWindow {
+    pub window: String,
+    pub blocks: u32,
+    pub network_hashrate: f64,
+    pub pools: Vec<PoolShare>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PoolStatsResponse {
+    pub height: u64,
+    pub windows: Vec<PoolWindow>,
+}
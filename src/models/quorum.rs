@@ -0,0 +1,133 @@
+use serde::Serialize;
+
+use crate::rpc::types::{RpcQuorumInfo, RpcQuorumMember};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QuorumMember {
+    pub pro_tx_hash: String,
+    pub service: Option<String>,
+    pub pub_key_operator: Option<String>,
+    /// False when the member was excluded during DKG
+    pub valid: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QuorumSummary {
+    pub quorum_type: String,
+    pub llmq_type: u32,
+    pub quorum_hash: String,
+    pub quorum_index: u32,
+    /// Height of the block the quorum was formed at
+    pub height: u64,
+    pub mined_block: String,
+    /// Height of the block the final commitment was mined in
+    pub mined_height: Option<u64>,
+    pub size: usize,
+    pub valid_members: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QuorumDetail {
+    pub quorum_type: String,
+    pub llmq_type: u32,
+    pub quorum_hash: String,
+    pub quorum_index: u32,
+    pub height: u64,
+    pub mined_block: String,
+    pub mined_height: Option<u64>,
+    pub size: usize,
+    pub valid_members: usize,
+    pub quorum_public_key: String,
+    /// Listed by the node's `quorum list`
+    pub active: bool,
+    pub members: Vec<QuorumMember>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QuorumTypeGroup {
+    pub quorum_type: String,
+    pub llmq_type: u32,
+    pub quorums: Vec<QuorumSummary>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QuorumListResponse {
+    pub total: usize,
+    pub types: Vec<QuorumTypeGroup>,
+}
+
+/// An active quorum a masternode is a member of
+#[derive(Debug, Serialize, Clone)]
+pub struct QuorumMembership {
+    pub quorum_type: String,
+    pub llmq_type: u32,
+    pub quorum_hash: String,
+    pub height: u64,
+    pub valid: bool,
+}
+
+impl QuorumMember {
+    pub fn from_rpc(member: &RpcQuorumMember) -> Self {
+        Self {
+            pro_tx_hash: member.pro_tx_hash.clone(),
+            service: member.service.clone(),
+            pub_key_operator: member.pub_key_operator.clone(),
+            valid: member.valid,
+        }
+    }
+}
+
+impl QuorumDetail {
+    pub fn from_rpc(
+        info: &RpcQuorumInfo,
+        llmq_type: u32,
+        mined_height: Option<u64>,
+        active: bool,
+    ) -> Self {
+        let members: Vec<QuorumMember> = info
+            .members
+            .iter()
+            .flatten()
+            .map(QuorumMember::from_rpc)
+            .collect();
+        Self {
+            quorum_type: info.quorum_type.clone(),
+            llmq_type,
+            quorum_hash: info.quorum_hash.clone(),
+            quorum_index: info.quorum_index,
+            height: info.height,
+            mined_block: info.mined_block.clone(),
+            mined_height,
+            size: members.len(),
+            valid_members: members.iter().filter(|m| m.valid).count(),
+            quorum_public_key: info.quorum_public_key.clone(),
+            active,
+            members,
+        }
+    }
+
+    pub fn summary(&self) -> QuorumSummary {
+        QuorumSummary {
+            quorum_type: self.quorum_type.clone(),
+            llmq_type: self.llmq_type,
+            quorum_hash: self.quorum_hash.clone(),
+            quorum_index: self.quorum_index,
+            height: self.height,
+            mined_block: self.mined_block.clone(),
+            mined_height: self.mined_height,
+            size: self.size,
+            valid_members: self.valid_members,
+        }
+    }
+
+    pub fn membership(&self, pro_tx_hash: &str) -> Option<QuorumMembership> {
+        let member = self.members.iter().find(|m| m.pro_tx_hash == pro_tx_hash)?;
+        Some(QuorumMembership {
+            quorum_type: self.quorum_type.clone(),
+            llmq_type: self.llmq_type,
+            quorum_hash: self.quorum_hash.clone(),
+            height: self.height,
+            valid: member.valid,
+        })
+    }
+}
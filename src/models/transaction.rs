@@ -161,7 +161,7 @@ impl TransactionDetail {
     }
 }
 
-fn build_special_payload(tx: &RpcTransaction) -> Option<Value> {
+pub(crate) fn build_special_payload(tx: &RpcTransaction) -> Option<Value> {
     if let Some(ref payload) = tx.pro_reg_tx {
         return Some(serde_json::json!({
             "type": "ProRegTx",
@@ -0,0 +1,134 @@
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+
+use crate::config::PlatformConfig;
+use crate::models::platform::PlatformProbe;
+use crate::AppState;
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// TCP reachability checks of Evo nodes' Platform HTTP ports. Results are cached per
+/// address, so each is connected to at most once per `cache.platform_probes` TTL; new
+/// connections are also capped per minute and in flight.
+pub struct Prober {
+    timeout: Duration,
+    per_minute: u32,
+    slots: Semaphore,
+    window: Mutex<(Instant, u32)>,
+}
+
+impl Prober {
+    pub fn new(config: &PlatformConfig) -> Self {
+        Self {
+            timeout: Duration::from_millis(config.probe_timeout_ms),
+            per_minute: config.probe_per_minute,
+            slots: Semaphore::new(config.probe_concurrency),
+            window: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// Take one probe from this minute's budget
+    fn allow(&self) -> bool {
+        let mut window = self.window.lock().unwrap();
+        if window.0.elapsed() >= WINDOW {
+            *window = (Instant::now(), 0);
+        }
+        window.1 += 1;
+        window.1 <= self.per_minute
+    }
+
+    async fn connect(&self, address: &str) -> PlatformProbe {
+        let failed = |error: String| PlatformProbe {
+            reachable: false,
+            latency_ms: None,
+            checked_at: chrono::Utc::now().timestamp() as u64,
+            error: Some(error),
+        };
+        // Addresses come from the chain; anything but a public IP would let anyone
+        // point the probe at the explorer's own network
+        let addr = match address.parse::<SocketAddr>() {
+            Ok(addr) if is_public(addr.ip()) => addr,
+            _ => return failed("Not a public IP address".to_string()),
+        };
+
+        let _slot = self
+            .slots
+            .acquire()
+            .await
+            .expect("probe semaphore is never closed");
+        let started = Instant::now();
+        match tokio::time::timeout(self.timeout, TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => PlatformProbe {
+                reachable: true,
+                latency_ms: Some(started.elapsed().as_millis() as u64),
+                checked_at: chrono::Utc::now().timestamp() as u64,
+                error: None,
+            },
+            Ok(Err(e)) => failed(e.to_string()),
+            Err(_) => failed("Timed out".to_string()),
+        }
+    }
+}
+
+/// Probe results by address, cached ones included. Addresses over this minute's budget
+/// are left out.
+pub async fn probe(state: &AppState, addresses: &[String]) -> HashMap<String, PlatformProbe> {
+    let mut results = HashMap::new();
+    let mut pending = Vec::new();
+    for address in addresses {
+        match state.cache.platform_probes.get(address).await {
+            Some(probe) => {
+                results.insert(address.clone(), probe);
+            }
+            None if state.platform.allow() => pending.push(address.clone()),
+            None => {}
+        }
+    }
+
+    let probes = join_all(pending.iter().map(|a| state.platform.connect(a))).await;
+    for (address, probe) in pending.into_iter().zip(probes) {
+        state
+            .cache
+            .platform_probes
+            .insert(address.clone(), probe.clone())
+            .await;
+        results.insert(address, probe);
+    }
+    results
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                // Shared address space (carrier-grade NAT)
+                || (a == 100 && (64..128).contains(&b))
+                || a == 0
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    // Unique local and link local
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
@@ -0,0 +1,219 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::models::pool::Miner;
+use crate::rpc::types::RpcTransaction;
+use crate::AppError;
+use crate::AppState;
+
+/// Pool database compiled into the binary
+const BUNDLED: &str = include_str!("pools.json");
+
+/// Blocks whose coinbase is fetched per batch
+const COINBASE_BATCH: usize = 100;
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+struct PoolEntry {
+    name: String,
+    link: Option<String>,
+    /// Substrings of the coinbase scriptSig, matched case-insensitively
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Payout addresses, matched against the miner output before tags
+    #[serde(default)]
+    addresses: Vec<String>,
+}
+
+/// Known mining pools, matched against coinbase transactions
+pub struct PoolDb {
+    pools: Vec<PoolEntry>,
+    by_address: HashMap<String, usize>,
+}
+
+impl PoolDb {
+    /// Bundled database, extended by the JSON file at `path`. File entries replace
+    /// bundled ones with the same name.
+    pub fn load(path: Option<&str>) -> Result<Self, AppError> {
+        let mut pools: Vec<PoolEntry> = match path {
+            Some(path) => {
+                let text = std::fs::read_to_string(path).map_err(|e| {
+                    AppError::Internal(format!("Failed to read {}: {}", path, e))
+                })?;
+                serde_json::from_str(&text)
+                    .map_err(|e| AppError::Internal(format!("{}: {}", path, e)))?
+            }
+            None => Vec::new(),
+        };
+
+        let bundled: Vec<PoolEntry> =
+            serde_json::from_str(BUNDLED).expect("bundled pool database is valid JSON");
+        let overridden: HashSet<String> = pools.iter().map(|p| p.name.clone()).collect();
+        pools.extend(bundled.into_iter().filter(|p| !overridden.contains(&p.name)));
+
+        for pool in &mut pools {
+            for tag in &mut pool.tags {
+                *tag = tag.to_lowercase();
+            }
+        }
+
+        let mut by_address = HashMap::new();
+        for (i, pool) in pools.iter().enumerate() {
+            for address in &pool.addresses {
+                by_address.entry(address.clone()).or_insert(i);
+            }
+        }
+
+        Ok(Self { pools, by_address })
+    }
+
+    pub fn identify(&self, coinbase: &RpcTransaction) -> Miner {
+        // The miner's output comes first; masternode, superblock and platform
+        // payments are appended after it and never identify the pool
+        let address = coinbase
+            .vout
+            .first()
+            .and_then(|o| o.script_pub_key.address.clone());
+
+        let pool = address
+            .as_ref()
+            .and_then(|a| self.by_address.get(a).copied())
+            .or_else(|| {
+                let text = coinbase_text(coinbase)?.to_lowercase();
+                self.pools
+                    .iter()
+                    .position(|p| p.tags.iter().any(|tag| text.contains(tag.as_str())))
+            })
+            .map(|i| &self.pools[i]);
+
+        Miner {
+            pool: pool.map(|p| p.name.clone()),
+            link: pool.and_then(|p| p.link.clone()),
+            address,
+        }
+    }
+}
+
+/// The coinbase scriptSig as text, with the non-UTF-8 height and extranonce bytes replaced
+fn coinbase_text(coinbase: &RpcTransaction) -> Option<String> {
+    let hex = coinbase.vin.first()?.coinbase.as_ref()?;
+    let bytes: Vec<u8> = (0..hex.len() / 2)
+        .filter_map(|i| u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok())
+        .collect();
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Miner of each block, by hash. Coinbases are looked up through their block, so
+/// this works without `-txindex`.
+pub async fn block_miners(state: &AppState, hashes: &[String]) -> Result<Vec<Miner>, AppError> {
+    let mut miners: HashMap<String, Miner> = HashMap::new();
+    let mut missing: Vec<String> = Vec::new();
+    for hash in hashes {
+        match state.cache.block_miners.get(hash).await {
+            Some(miner) => {
+                miners.insert(hash.clone(), miner);
+            }
+            None => missing.push(hash.clone()),
+        }
+    }
+
+    for chunk in missing.chunks(COINBASE_BATCH) {
+        let blocks = state.rpc.get_blocks(chunk).await?;
+        let coinbases: Vec<(String, String)> = blocks
+            .iter()
+            .filter_map(|b| Some((b.txids()?.first()?.clone(), b.hash.clone())))
+            .collect();
+        let txs = state.rpc.get_block_transactions(&coinbases).await?;
+
+        for ((_, hash), tx) in coinbases.into_iter().zip(txs) {
+            // The genesis coinbase can't be fetched; it stays unknown
+            let Ok(tx) = tx else {
+                continue;
+            };
+            let miner = state.pools.identify(&tx);
+            state.cache.block_miners.insert(hash.clone(), miner.clone()).await;
+            miners.insert(hash, miner);
+        }
+    }
+
+    Ok(hashes
+        .iter()
+        .map(|hash| miners.get(hash).cloned().unwrap_or_default())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn coinbase(script_sig: &str, outputs: &[&str]) -> RpcTransaction {
+        let vout: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .map(|(n, address)| {
+                json!({
+                    "value": 1.0,
+                    "valueSat": 100_000_000,
+                    "n": n,
+                    "scriptPubKey": { "asm": "", "hex": "", "type": "pubkeyhash", "address": address }
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "txid": "aa",
+            "version": 3,
+            "type": 5,
+            "size": 200,
+            "locktime": 0,
+            "instantlock": false,
+            "instantlock_internal": false,
+            "vin": [{ "coinbase": script_sig, "sequence": 0 }],
+            "vout": vout,
+        }))
+        .unwrap()
+    }
+
+    fn db() -> PoolDb {
+        // Tests run in parallel, so each load gets its own file
+        static FILES: AtomicUsize = AtomicUsize::new(0);
+        let n = FILES.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "dashbook-pools-{}-{}.json",
+            std::process::id(),
+            n
+        ));
+        std::fs::write(
+            &path,
+            r#"[{ "name": "Example Pool", "tags": ["/example/"], "addresses": ["XminerPayout"] }]"#,
+        )
+        .unwrap();
+        let db = PoolDb::load(path.to_str());
+        std::fs::remove_file(&path).unwrap();
+        db.unwrap()
+    }
+
+    #[test]
+    fn matches_payout_address_of_miner_output() {
+        let miner = db().identify(&coinbase("03abcd", &["XminerPayout", "XmnPayee"]));
+        assert_eq!(miner.pool.as_deref(), Some("Example Pool"));
+        assert_eq!(miner.address.as_deref(), Some("XminerPayout"));
+    }
+
+    #[test]
+    fn ignores_payout_address_in_masternode_output() {
+        let miner = db().identify(&coinbase("03abcd", &["XsoloMiner", "XminerPayout"]));
+        assert_eq!(miner.pool, None);
+    }
+
+    #[test]
+    fn matches_tags_case_insensitively() {
+        // "/EXAMPLE/" in the scriptSig
+        let miner = db().identify(&coinbase("2f4558414d504c452f", &["XsoloMiner"]));
+        assert_eq!(miner.pool.as_deref(), Some("Example Pool"));
+
+        let miner = db().identify(&coinbase("4d696e656420627920416e74506f6f6c", &["Xother"]));
+        assert_eq!(miner.pool.as_deref(), Some("AntPool"));
+    }
+}
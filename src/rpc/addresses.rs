@@ -52,4 +52,15 @@ impl DashRpcClient {
         }
         self.call("getaddressdeltas", json!([params])).await
     }
+
+    pub async fn get_address_mempool(
+        &self,
+        address: &str,
+    ) -> Result<Vec<RpcAddressMempoolEntry>, AppError> {
+        self.call(
+            "getaddressmempool",
+            json!([{"addresses": [address]}]),
+        )
+        .await
+    }
 }
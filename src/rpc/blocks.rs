@@ -17,10 +17,25 @@ impl DashRpcClient {
         self.call("getblock", json!([hash, verbosity])).await
     }
 
+    /// Hashes for several heights in one batch
+    pub async fn get_block_hashes(&self, heights: &[u64]) -> Result<Vec<String>, AppError> {
+        let calls = heights.iter().map(|h| ("getblockhash", json!([h]))).collect();
+        self.batch(calls).await?.into_iter().collect()
+    }
+
     pub async fn get_block_header(&self, hash: &str) -> Result<RpcBlock, AppError> {
         self.call("getblockheader", json!([hash, true])).await
     }
 
+    /// Headers for several blocks in one batch
+    pub async fn get_block_headers(&self, hashes: &[String]) -> Result<Vec<RpcBlock>, AppError> {
+        let calls = hashes
+            .iter()
+            .map(|hash| ("getblockheader", json!([hash, true])))
+            .collect();
+        self.batch(calls).await?.into_iter().collect()
+    }
+
     pub async fn get_best_block_hash(&self) -> Result<String, AppError> {
         self.call("getbestblockhash", json!([])).await
     }
@@ -29,10 +44,32 @@ impl DashRpcClient {
         self.call("getblockstats", json!([height])).await
     }
 
+    /// Stats for several heights in one batch; each one succeeds or fails on its own
+    pub async fn get_block_stats_batch(
+        &self,
+        heights: &[u64],
+    ) -> Result<Vec<Result<RpcBlockStats, AppError>>, AppError> {
+        let calls = heights.iter().map(|h| ("getblockstats", json!([h]))).collect();
+        self.batch(calls).await
+    }
+
+    /// Blocks at verbosity 1 for several hashes in one batch
+    pub async fn get_blocks(&self, hashes: &[String]) -> Result<Vec<RpcBlock>, AppError> {
+        let calls = hashes
+            .iter()
+            .map(|hash| ("getblock", json!([hash, 1])))
+            .collect();
+        self.batch(calls).await?.into_iter().collect()
+    }
+
     pub async fn get_blockchain_info(&self) -> Result<RpcBlockchainInfo, AppError> {
         self.call("getblockchaininfo", json!([])).await
     }
 
+    pub async fn get_deployment_info(&self) -> Result<RpcDeploymentInfo, AppError> {
+        self.call("getdeploymentinfo", json!([])).await
+    }
+
     pub async fn get_raw_block(&self, hash: &str) -> Result<String, AppError> {
         self.call("getblock", json!([hash, 0])).await
     }
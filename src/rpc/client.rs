@@ -1,14 +1,33 @@
+use super::pool::Backend;
+use crate::config::RpcConfig;
+use crate::models::network::RpcBackendStatus;
 use crate::AppError;
+use futures::future::join_all;
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
+/// Calls that must not be repeated on another node once the request may have been delivered
+const NON_IDEMPOTENT: [&str; 1] = ["sendrawtransaction"];
+
+/// How often backend heights are compared
+const HEALTH_INTERVAL: Duration = Duration::from_secs(10);
+
+fn is_idempotent(method: &str) -> bool {
+    !NON_IDEMPOTENT.contains(&method)
+}
+
+/// JSON-RPC client over one or more Dash Core nodes. Requests go to the first node in
+/// rotation (config order), and fail over to the next one on transport errors.
 pub struct DashRpcClient {
     client: Client,
-    url: String,
-    username: String,
-    password: String,
+    backends: Vec<Backend>,
+    /// Nodes more than this many blocks behind the best tip leave rotation
+    max_lag: u64,
     request_id: AtomicU64,
 }
 
@@ -16,7 +35,6 @@ pub struct DashRpcClient {
 struct RpcResponse<T> {
     result: Option<T>,
     error: Option<RpcError>,
-    #[allow(dead_code)]
     id: Value,
 }
 
@@ -27,21 +45,85 @@ struct RpcError {
 }
 
 impl DashRpcClient {
-    pub fn new(url: String, username: String, password: String) -> Self {
+    pub fn new(config: &RpcConfig) -> Self {
         let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(Duration::from_secs(config.timeout_secs))
             .build()
             .expect("Failed to build HTTP client");
 
         Self {
             client,
-            url,
-            username,
-            password,
+            backends: config.nodes.iter().cloned().map(Backend::new).collect(),
+            max_lag: config.max_lag,
             request_id: AtomicU64::new(1),
         }
     }
 
+    /// Post a request body, failing over across backends. Nodes in rotation are tried
+    /// first, then the rest as a last resort.
+    async fn send(&self, body: &Value, idempotent: bool) -> Result<String, AppError> {
+        let (up, down): (Vec<&Backend>, Vec<&Backend>) =
+            self.backends.iter().partition(|b| b.in_rotation());
+
+        let mut last_error = None;
+        for backend in up.into_iter().chain(down) {
+            match backend.post(&self.client, body).await {
+                Ok(text) => return Ok(text),
+                Err(e) => {
+                    // Connection failures mean the node never saw the request
+                    let retry = idempotent || e.is_connect();
+                    backend.mark_down(e.to_string());
+                    last_error = Some(e);
+                    if !retry {
+                        break;
+                    }
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) => Err(e.into()),
+            None => Err(AppError::Internal("No RPC backends configured".into())),
+        }
+    }
+
+    /// Probe every backend and keep only those near the best tip in rotation
+    pub async fn check_health(&self) {
+        let heights = join_all(self.backends.iter().map(|b| b.probe(&self.client))).await;
+        let best = heights.iter().flatten().copied().max().unwrap_or(0);
+
+        for (backend, height) in self.backends.iter().zip(heights) {
+            let result = match height {
+                Ok(h) if best - h > self.max_lag => Err(format!(
+                    "{} blocks behind best tip {}",
+                    best - h,
+                    best
+                )),
+                Ok(_) => Ok(()),
+                Err(e) => Err(e),
+            };
+            backend.set_rotation(result);
+        }
+    }
+
+    pub async fn run_health_checks(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(HEALTH_INTERVAL);
+        loop {
+            interval.tick().await;
+            self.check_health().await;
+        }
+    }
+
+    /// Health of every backend, marking the one requests currently go to
+    pub fn backend_status(&self) -> Vec<RpcBackendStatus> {
+        let active = self.backends.iter().position(|b| b.in_rotation());
+        self.backends
+            .iter()
+            .enumerate()
+            .map(|(i, b)| b.status(active == Some(i)))
+            .collect()
+    }
+
     pub async fn call<T: DeserializeOwned>(
         &self,
         method: &str,
@@ -56,31 +138,84 @@ impl DashRpcClient {
             "params": params,
         });
 
-        let response = self
-            .client
-            .post(&self.url)
-            .basic_auth(&self.username, Some(&self.password))
-            .header("content-type", "text/plain")
-            .json(&body)
-            .send()
-            .await?;
-
-        let resp_text = response.text().await?;
+        let resp_text = self.send(&body, is_idempotent(method)).await?;
 
         let rpc_resp: RpcResponse<T> =
             serde_json::from_str(&resp_text).map_err(|e| AppError::Internal(
                 format!("Failed to parse RPC response for {}: {} -- response: {}", method, e, &resp_text[..resp_text.len().min(500)])
             ))?;
 
-        if let Some(err) = rpc_resp.error {
+        rpc_resp.into_result(method)
+    }
+
+    /// Send several calls as one JSON-RPC array in a single POST. Results come back
+    /// in request order; each call succeeds or fails on its own.
+    pub async fn batch<T: DeserializeOwned>(
+        &self,
+        calls: Vec<(&str, Value)>,
+    ) -> Result<Vec<Result<T, AppError>>, AppError> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let first_id = self
+            .request_id
+            .fetch_add(calls.len() as u64, Ordering::Relaxed);
+
+        let body: Value = calls
+            .iter()
+            .enumerate()
+            .map(|(i, (method, params))| {
+                json!({
+                    "jsonrpc": "1.0",
+                    "id": first_id + i as u64,
+                    "method": method,
+                    "params": params,
+                })
+            })
+            .collect();
+
+        let resp_text = self.send(&body, calls.iter().all(|(m, _)| is_idempotent(m))).await?;
+
+        let responses: Vec<RpcResponse<Value>> =
+            serde_json::from_str(&resp_text).map_err(|e| AppError::Internal(
+                format!("Failed to parse RPC batch response: {} -- response: {}", e, resp_text.chars().take(500).collect::<String>())
+            ))?;
+
+        // The node may answer a batch in any order
+        let mut by_id: HashMap<u64, RpcResponse<Value>> = responses
+            .into_iter()
+            .filter_map(|r| Some((r.id.as_u64()?, r)))
+            .collect();
+
+        let results = calls
+            .iter()
+            .enumerate()
+            .map(|(i, (method, _))| {
+                let resp = by_id.remove(&(first_id + i as u64)).ok_or_else(|| {
+                    AppError::Internal(format!("RPC batch response missing {}", method))
+                })?;
+                let value = resp.into_result(method)?;
+                serde_json::from_value(value).map_err(|e| {
+                    AppError::Internal(format!("Failed to parse RPC response for {}: {}", method, e))
+                })
+            })
+            .collect();
+
+        Ok(results)
+    }
+}
+
+impl<T> RpcResponse<T> {
+    fn into_result(self, method: &str) -> Result<T, AppError> {
+        if let Some(err) = self.error {
             return Err(AppError::Rpc {
                 code: err.code,
                 message: err.message,
             });
         }
 
-        rpc_resp
-            .result
+        self.result
             .ok_or_else(|| AppError::Internal(format!("RPC {} returned null result", method)))
     }
 }
@@ -15,11 +15,50 @@ impl DashRpcClient {
         self.call("masternode", json!(["count"])).await
     }
 
-    pub async fn get_protx_list(&self) -> Result<Vec<RpcProtx>, AppError> {
-        self.call("protx", json!(["list", "registered", 1])).await
+    /// Masternode payees recorded for the block `block_hash`
+    pub async fn get_masternode_payments(
+        &self,
+        block_hash: &str,
+    ) -> Result<Vec<RpcMasternodePayments>, AppError> {
+        self.call("masternode", json!(["payments", block_hash, 1])).await
+    }
+
+    /// `masternode payments` for each block in one batch; each one succeeds or fails on its own
+    pub async fn get_masternode_payments_at(
+        &self,
+        block_hashes: &[String],
+    ) -> Result<Vec<Result<Vec<RpcMasternodePayments>, AppError>>, AppError> {
+        let calls = block_hashes
+            .iter()
+            .map(|hash| ("masternode", json!(["payments", hash, 1])))
+            .collect();
+        self.batch(calls).await
+    }
+
+    /// Every registered masternode with its full state as of `height`
+    pub async fn get_protx_list(&self, height: u64) -> Result<Vec<RpcProtx>, AppError> {
+        self.call("protx", json!(["list", "registered", true, height])).await
+    }
+
+    /// ProTx hashes of the masternodes that were valid at `height`
+    pub async fn get_valid_protx_hashes(&self, height: u64) -> Result<Vec<String>, AppError> {
+        self.call("protx", json!(["list", "valid", false, height])).await
     }
 
     pub async fn get_protx_info(&self, protx_hash: &str) -> Result<RpcProtx, AppError> {
         self.call("protx", json!(["info", protx_hash])).await
     }
+
+    /// `protx info` as of each block in one batch; each one succeeds or fails on its own
+    pub async fn get_protx_info_at(
+        &self,
+        protx_hash: &str,
+        block_hashes: &[String],
+    ) -> Result<Vec<Result<RpcProtx, AppError>>, AppError> {
+        let calls = block_hashes
+            .iter()
+            .map(|hash| ("protx", json!(["info", protx_hash, hash])))
+            .collect();
+        self.batch(calls).await
+    }
 }
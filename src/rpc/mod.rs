@@ -4,6 +4,7 @@ mod client;
 mod governance;
 mod masternodes;
 mod network;
+mod pool;
 mod quorums;
 mod transactions;
 pub mod types;
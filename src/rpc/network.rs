@@ -8,6 +8,10 @@ impl DashRpcClient {
         self.call("getnetworkinfo", json!([])).await
     }
 
+    pub async fn get_peer_info(&self) -> Result<Vec<RpcPeerInfo>, AppError> {
+        self.call("getpeerinfo", json!([])).await
+    }
+
     pub async fn get_mempool_info(&self) -> Result<RpcMempoolInfo, AppError> {
         self.call("getmempoolinfo", json!([])).await
     }
@@ -20,7 +24,21 @@ impl DashRpcClient {
         self.call("spork", json!(["show"])).await
     }
 
-    pub async fn estimate_fee(&self, n_blocks: u32) -> Result<serde_json::Value, AppError> {
-        self.call("estimatesmartfee", json!([n_blocks])).await
+    /// Estimated network hashes per second over the last `blocks` blocks
+    pub async fn get_network_hash_ps(&self, blocks: u64) -> Result<f64, AppError> {
+        self.call("getnetworkhashps", json!([blocks])).await
+    }
+
+    /// `estimatesmartfee` for several (target, mode) pairs in one batch; `mode` is
+    /// `CONSERVATIVE` or `ECONOMICAL`
+    pub async fn estimate_smart_fees(
+        &self,
+        requests: &[(u32, &str)],
+    ) -> Result<Vec<Result<RpcFeeEstimate, AppError>>, AppError> {
+        let calls = requests
+            .iter()
+            .map(|(target, mode)| ("estimatesmartfee", json!([target, mode])))
+            .collect();
+        self.batch(calls).await
     }
 }
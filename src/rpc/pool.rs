@@ -0,0 +1,325 @@
+use reqwest::{Client, Response, StatusCode};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::config::{RpcAuth, RpcNodeConfig};
+use crate::models::network::RpcBackendStatus;
+
+/// One Dash Core node behind the RPC client
+pub struct Backend {
+    url: String,
+    auth: RpcAuth,
+    /// Username and password currently in use; loaded lazily for file-based auth
+    credentials: Mutex<Option<(String, String)>>,
+    health: Mutex<Health>,
+}
+
+#[derive(Debug, Clone)]
+struct Health {
+    in_rotation: bool,
+    height: Option<u64>,
+    latency_ms: Option<u64>,
+    error: Option<String>,
+    checked_at: Option<u64>,
+}
+
+impl Backend {
+    pub fn new(node: RpcNodeConfig) -> Self {
+        Self {
+            url: node.url,
+            credentials: Mutex::new(match node.auth {
+                RpcAuth::Password {
+                    ref username,
+                    ref password,
+                } => Some((username.clone(), password.clone())),
+                _ => None,
+            }),
+            auth: node.auth,
+            // Optimistic until the first health check says otherwise
+            health: Mutex::new(Health {
+                in_rotation: true,
+                height: None,
+                latency_ms: None,
+                error: None,
+                checked_at: None,
+            }),
+        }
+    }
+
+    pub fn in_rotation(&self) -> bool {
+        self.health.lock().unwrap().in_rotation
+    }
+
+    pub async fn post(&self, client: &Client, body: &Value) -> Result<String, reqwest::Error> {
+        let response = self.send(client, body).await?;
+        // The node rewrote its cookie (or the password file changed): reload and retry once
+        let response = if response.status() == StatusCode::UNAUTHORIZED && self.reload_credentials() {
+            self.send(client, body).await?
+        } else {
+            response
+        };
+        // Still rejected: treat it like an unreachable node. Other statuses carry
+        // JSON-RPC errors in the body.
+        if response.status() == StatusCode::UNAUTHORIZED {
+            response.error_for_status_ref()?;
+        }
+        response.text().await
+    }
+
+    async fn send(&self, client: &Client, body: &Value) -> Result<Response, reqwest::Error> {
+        let (username, password) = self.credentials();
+        client
+            .post(&self.url)
+            .basic_auth(username, Some(password))
+            .header("content-type", "text/plain")
+            .json(body)
+            .send()
+            .await
+    }
+
+    fn credentials(&self) -> (String, String) {
+        if let Some(creds) = self.credentials.lock().unwrap().clone() {
+            return creds;
+        }
+        self.reload_credentials();
+        self.credentials.lock().unwrap().clone().unwrap_or_default()
+    }
+
+    /// Re-read file-based credentials. Returns true when they changed.
+    fn reload_credentials(&self) -> bool {
+        let loaded = match self.auth {
+            RpcAuth::Password { .. } => return false,
+            RpcAuth::PasswordFile {
+                ref username,
+                ref path,
+            } => read_secret(path).map(|password| (username.clone(), password)),
+            RpcAuth::Cookie(ref path) => read_secret(path).and_then(|cookie| {
+                cookie
+                    .split_once(':')
+                    .map(|(user, pass)| (user.to_string(), pass.to_string()))
+                    .ok_or_else(|| format!("{} is not a valid cookie file", path.display()))
+            }),
+        };
+
+        match loaded {
+            Ok(creds) => {
+                let mut current = self.credentials.lock().unwrap();
+                let changed = current.as_ref() != Some(&creds);
+                *current = Some(creds);
+                changed
+            }
+            Err(e) => {
+                tracing::warn!("RPC credentials for {} unavailable: {}", self.url, e);
+                false
+            }
+        }
+    }
+
+    /// Take the node out of rotation after a failed request
+    pub fn mark_down(&self, error: String) {
+        let mut health = self.health.lock().unwrap();
+        if health.in_rotation {
+            tracing::warn!("RPC backend {} out of rotation: {}", self.url, error);
+        }
+        health.in_rotation = false;
+        health.error = Some(error);
+    }
+
+    /// Query the node's height
+    pub async fn probe(&self, client: &Client) -> Result<u64, String> {
+        let body = json!({
+            "jsonrpc": "1.0",
+            "id": "health",
+            "method": "getblockcount",
+            "params": [],
+        });
+
+        let started = Instant::now();
+        let result = match self.post(client, &body).await {
+            Ok(text) => match serde_json::from_str::<Value>(&text) {
+                Ok(resp) => match resp.get("result").and_then(|r| r.as_u64()) {
+                    Some(height) => Ok(height),
+                    None => Err(resp
+                        .pointer("/error/message")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or("getblockcount returned no result")
+                        .to_string()),
+                },
+                Err(e) => Err(format!("Invalid response: {}", e)),
+            },
+            Err(e) => Err(e.to_string()),
+        };
+
+        let mut health = self.health.lock().unwrap();
+        health.checked_at = Some(chrono::Utc::now().timestamp() as u64);
+        match result {
+            Ok(height) => {
+                health.height = Some(height);
+                health.latency_ms = Some(started.elapsed().as_millis() as u64);
+            }
+            Err(_) => health.latency_ms = None,
+        }
+        result
+    }
+
+    /// Put the node back in rotation, or take it out, after a health check round
+    pub fn set_rotation(&self, result: Result<(), String>) {
+        let mut health = self.health.lock().unwrap();
+        match result {
+            Ok(()) => {
+                if !health.in_rotation {
+                    tracing::info!("RPC backend {} back in rotation", self.url);
+                }
+                health.in_rotation = true;
+                health.error = None;
+            }
+            Err(e) => {
+                if health.in_rotation {
+                    tracing::warn!("RPC backend {} out of rotation: {}", self.url, e);
+                }
+                health.in_rotation = false;
+                health.error = Some(e);
+            }
+        }
+    }
+
+    pub fn status(&self, active: bool) -> RpcBackendStatus {
+        let health = self.health.lock().unwrap().clone();
+        RpcBackendStatus {
+            url: display_url(&self.url),
+            active,
+            in_rotation: health.in_rotation,
+            height: health.height,
+            latency_ms: health.latency_ms,
+            error: health.error,
+            checked_at: health.checked_at,
+        }
+    }
+}
+
+/// Host and port only, so paths or credentials in the URL never reach the API
+fn display_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(u) => match (u.host_str(), u.port_or_known_default()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            _ => url.to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+fn read_secret(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim().to_string())
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::net::SocketAddr;
+    use tokio::net::TcpListener;
+
+    use crate::config::{RpcAuth, RpcConfig, RpcNodeConfig};
+    use crate::rpc::DashRpcClient;
+
+    /// Mock node answering every call with `height`, as `getblockcount` would
+    async fn serve(listener: TcpListener, height: u64) {
+        let app = Router::new().route(
+            "/",
+            // Like dashd, the body is JSON sent as text/plain
+            post(move |body: String| async move {
+                let req: serde_json::Value = serde_json::from_str(&body).unwrap();
+                Json(serde_json::json!({ "result": height, "error": null, "id": req["id"] }))
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    }
+
+    /// An address nothing listens on
+    async fn closed_addr() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    fn client(addrs: &[SocketAddr]) -> DashRpcClient {
+        DashRpcClient::new(&RpcConfig {
+            nodes: addrs
+                .iter()
+                .map(|addr| RpcNodeConfig {
+                    url: format!("http://{}/", addr),
+                    auth: RpcAuth::Password {
+                        username: "user".into(),
+                        password: "pass".into(),
+                    },
+                })
+                .collect(),
+            max_lag: 2,
+            timeout_secs: 5,
+        })
+    }
+
+    #[tokio::test]
+    async fn fails_over_when_first_node_is_down() {
+        let down = closed_addr().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let up = listener.local_addr().unwrap();
+        serve(listener, 200).await;
+
+        let rpc = client(&[down, up]);
+        assert_eq!(rpc.get_block_count().await.unwrap(), 200);
+
+        let status = rpc.backend_status();
+        assert!(!status[0].in_rotation);
+        assert!(status[0].error.is_some());
+        assert!(status[1].in_rotation && status[1].active);
+
+        // The node out of rotation is no longer tried first
+        assert_eq!(rpc.get_block_count().await.unwrap(), 200);
+    }
+
+    #[tokio::test]
+    async fn health_check_returns_recovered_node_to_rotation() {
+        let first = closed_addr().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let second = listener.local_addr().unwrap();
+        serve(listener, 201).await;
+
+        let rpc = client(&[first, second]);
+        assert_eq!(rpc.get_block_count().await.unwrap(), 201);
+        assert!(!rpc.backend_status()[0].in_rotation);
+
+        // The first node comes back; only a health check puts it in rotation again
+        serve(TcpListener::bind(first).await.unwrap(), 200).await;
+        assert_eq!(rpc.get_block_count().await.unwrap(), 201);
+
+        rpc.check_health().await;
+        let status = rpc.backend_status();
+        assert!(status[0].in_rotation && status[0].active);
+        assert_eq!(status[0].height, Some(200));
+        assert!(status[0].error.is_none());
+        assert_eq!(rpc.get_block_count().await.unwrap(), 200);
+    }
+
+    #[tokio::test]
+    async fn health_check_drops_lagging_node() {
+        let (a, b) = (
+            TcpListener::bind("127.0.0.1:0").await.unwrap(),
+            TcpListener::bind("127.0.0.1:0").await.unwrap(),
+        );
+        let addrs = [a.local_addr().unwrap(), b.local_addr().unwrap()];
+        serve(a, 100).await;
+        serve(b, 110).await;
+
+        let rpc = client(&addrs);
+        rpc.check_health().await;
+        let status = rpc.backend_status();
+        assert!(!status[0].in_rotation);
+        assert!(status[1].in_rotation && status[1].active);
+        assert_eq!(rpc.get_block_count().await.unwrap(), 110);
+    }
+}
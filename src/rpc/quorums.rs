@@ -16,4 +16,16 @@ impl DashRpcClient {
     ) -> Result<RpcQuorumInfo, AppError> {
         self.call("quorum", json!(["info", quorum_type, quorum_hash])).await
     }
+
+    /// `quorum info` for several (LLMQ type, quorum hash) pairs in one batch
+    pub async fn get_quorum_infos(
+        &self,
+        quorums: &[(u32, String)],
+    ) -> Result<Vec<Result<RpcQuorumInfo, AppError>>, AppError> {
+        let calls = quorums
+            .iter()
+            .map(|(quorum_type, hash)| ("quorum", json!(["info", quorum_type, hash])))
+            .collect();
+        self.batch(calls).await
+    }
 }
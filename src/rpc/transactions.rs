@@ -12,9 +12,33 @@ impl DashRpcClient {
         self.call("getrawtransaction", json!([txid, true])).await
     }
 
+    /// Several transactions in one batch; unknown txids fail individually
+    pub async fn get_raw_transactions(
+        &self,
+        txids: &[String],
+    ) -> Result<Vec<Result<RpcTransaction, AppError>>, AppError> {
+        let calls = txids
+            .iter()
+            .map(|txid| ("getrawtransaction", json!([txid, true])))
+            .collect();
+        self.batch(calls).await
+    }
+
+    /// Transactions looked up in a known block, which works without `-txindex`
+    pub async fn get_block_transactions(
+        &self,
+        txs: &[(String, String)],
+    ) -> Result<Vec<Result<RpcTransaction, AppError>>, AppError> {
+        let calls = txs
+            .iter()
+            .map(|(txid, blockhash)| ("getrawtransaction", json!([txid, true, blockhash])))
+            .collect();
+        self.batch(calls).await
+    }
+
     pub async fn get_raw_mempool_verbose(
         &self,
-    ) -> Result<HashMap<String, serde_json::Value>, AppError> {
+    ) -> Result<HashMap<String, RpcMempoolEntry>, AppError> {
         self.call("getrawmempool", json!([true])).await
     }
 
@@ -22,6 +46,18 @@ impl DashRpcClient {
         self.call("getrawmempool", json!([false])).await
     }
 
+    /// Mempool entries for a few transactions; txids that left the mempool fail individually
+    pub async fn get_mempool_entries(
+        &self,
+        txids: &[String],
+    ) -> Result<Vec<Result<RpcMempoolEntry, AppError>>, AppError> {
+        let calls = txids
+            .iter()
+            .map(|txid| ("getmempoolentry", json!([txid])))
+            .collect();
+        self.batch(calls).await
+    }
+
     pub async fn send_raw_transaction(&self, hex: &str) -> Result<String, AppError> {
         self.call("sendrawtransaction", json!([hex])).await
     }
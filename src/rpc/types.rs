@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
 // ============ Blocks ============
 
@@ -39,7 +40,7 @@ impl RpcBlock {
     pub fn transactions(&self) -> Option<Vec<RpcTransaction>> {
         self.tx.as_ref().and_then(|v| {
             if let serde_json::Value::Array(arr) = v {
-                if arr.first().map_or(false, |v| v.is_object()) {
+                if arr.first().is_some_and(|v| v.is_object()) {
                     serde_json::from_value(serde_json::Value::Array(arr.clone())).ok()
                 } else {
                     None
@@ -54,7 +55,7 @@ impl RpcBlock {
     pub fn txids(&self) -> Option<Vec<String>> {
         self.tx.as_ref().and_then(|v| {
             if let serde_json::Value::Array(arr) = v {
-                if arr.first().map_or(false, |v| v.is_string()) {
+                if arr.first().is_some_and(|v| v.is_string()) {
                     serde_json::from_value(serde_json::Value::Array(arr.clone())).ok()
                 } else {
                     None
@@ -72,13 +73,14 @@ pub struct RpcCbTxPayload {
     pub height: u64,
     #[serde(rename = "merkleRootMNList")]
     pub merkle_root_mn_list: String,
-    #[serde(rename = "merkleRootQuorums")]
+    /// Fields below are absent from older coinbase payload versions
+    #[serde(rename = "merkleRootQuorums", default)]
     pub merkle_root_quorums: String,
-    #[serde(rename = "bestCLHeightDiff")]
+    #[serde(rename = "bestCLHeightDiff", default)]
     pub best_cl_height_diff: u64,
-    #[serde(rename = "bestCLSignature")]
+    #[serde(rename = "bestCLSignature", default)]
     pub best_cl_signature: String,
-    #[serde(rename = "creditPoolBalance")]
+    #[serde(rename = "creditPoolBalance", default)]
     pub credit_pool_balance: f64,
 }
 
@@ -88,6 +90,9 @@ pub struct RpcBlockStats {
     pub avgfeerate: f64,
     pub avgtxsize: u64,
     pub blockhash: String,
+    /// 10th, 25th, 50th, 75th and 90th percentile fee rates in duffs per byte
+    #[serde(default)]
+    pub feerate_percentiles: Vec<f64>,
     pub height: u64,
     pub ins: u32,
     pub maxfee: f64,
@@ -150,6 +155,20 @@ pub struct RpcTransaction {
     pub fee: Option<f64>,
 }
 
+impl RpcTransaction {
+    /// ProTxHash of the masternode a provider transaction (types 1-4) refers to
+    pub fn pro_tx_hash(&self) -> Option<String> {
+        if self.pro_reg_tx.is_some() {
+            return Some(self.txid.clone());
+        }
+        [&self.pro_up_serv_tx, &self.pro_up_reg_tx, &self.pro_up_rev_tx]
+            .into_iter()
+            .flatten()
+            .find_map(|payload| payload.get("proTxHash").and_then(|h| h.as_str()))
+            .map(|h| h.to_string())
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct RpcTxInput {
     pub txid: Option<String>,
@@ -302,6 +321,18 @@ pub struct RpcAddressDelta {
     pub address: String,
 }
 
+/// One `getaddressmempool` entry. Spends carry the outpoint they consume and a negative amount.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcAddressMempoolEntry {
+    pub address: String,
+    pub txid: String,
+    pub index: u32,
+    pub satoshis: i64,
+    pub timestamp: u64,
+    pub prevtxid: Option<String>,
+    pub prevout: Option<u32>,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct RpcAddressUtxo {
     pub address: String,
@@ -388,6 +419,9 @@ pub struct RpcMasternodeState {
     pub voting_address: String,
     #[serde(rename = "payoutAddress")]
     pub payout_address: String,
+    /// Only present when the operator set one
+    #[serde(rename = "operatorPayoutAddress")]
+    pub operator_payout_address: Option<String>,
     #[serde(rename = "pubKeyOperator")]
     pub pub_key_operator: String,
     #[serde(rename = "platformNodeID")]
@@ -418,6 +452,33 @@ pub struct RpcMasternodeMetaInfo {
     pub platform_ban_height_updated: Option<u64>,
 }
 
+/// `masternode payments` for one block
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcMasternodePayments {
+    pub height: u64,
+    pub blockhash: String,
+    /// Duffs paid to masternodes in the block
+    pub amount: i64,
+    #[serde(default)]
+    pub masternodes: Vec<RpcMasternodePayment>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcMasternodePayment {
+    #[serde(rename = "proTxHash")]
+    pub pro_tx_hash: String,
+    pub amount: i64,
+    /// Owner payout first, then the operator's when it takes a share
+    #[serde(default)]
+    pub payees: Vec<RpcMasternodePayeeOutput>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcMasternodePayeeOutput {
+    pub address: String,
+    pub amount: i64,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct RpcMasternodeCount {
     pub total: u32,
@@ -455,6 +516,17 @@ pub struct RpcNetworkInfo {
     pub warnings: String,
 }
 
+/// One `getpeerinfo` entry
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcPeerInfo {
+    pub addr: String,
+    /// Protocol version
+    pub version: u32,
+    pub subver: String,
+    /// Set once the peer proved it runs this masternode
+    pub verified_proregtx_hash: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct RpcBlockchainInfo {
     pub chain: String,
@@ -470,6 +542,20 @@ pub struct RpcBlockchainInfo {
     pub size_on_disk: u64,
     pub pruned: bool,
     pub warnings: String,
+    /// Dropped in favour of `getdeploymentinfo` by newer Core versions
+    #[serde(default)]
+    pub softforks: HashMap<String, RpcDeployment>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcDeployment {
+    #[serde(default)]
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcDeploymentInfo {
+    pub deployments: HashMap<String, RpcDeployment>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -486,6 +572,56 @@ pub struct RpcMempoolInfo {
     pub unbroadcastcount: u64,
 }
 
+/// `getrawmempool true` entry
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcMempoolEntry {
+    pub size: u64,
+    /// Deprecated upstream in favour of `fees`
+    pub fee: Option<f64>,
+    pub fees: Option<RpcMempoolFees>,
+    pub time: u64,
+    pub height: u64,
+    #[serde(default)]
+    pub ancestorcount: u64,
+    #[serde(default)]
+    pub ancestorsize: u64,
+    #[serde(default)]
+    pub descendantcount: u64,
+    #[serde(default)]
+    pub depends: Vec<String>,
+    #[serde(default)]
+    pub instantlock: bool,
+}
+
+impl RpcMempoolEntry {
+    pub fn base_fee(&self) -> f64 {
+        self.fees
+            .as_ref()
+            .map(|f| f.base)
+            .or(self.fee)
+            .unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcMempoolFees {
+    pub base: f64,
+    pub modified: f64,
+    pub ancestor: f64,
+    pub descendant: f64,
+}
+
+/// `estimatesmartfee` result
+#[derive(Debug, Deserialize, Clone)]
+pub struct RpcFeeEstimate {
+    /// DASH per kB; missing when the node has too little data
+    pub feerate: Option<f64>,
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Target the estimate was actually made for
+    pub blocks: u32,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct RpcChainTxStats {
     pub time: u64,
@@ -576,7 +712,8 @@ pub struct RpcQuorumInfo {
     pub quorum_type: String,
     #[serde(rename = "quorumHash")]
     pub quorum_hash: String,
-    #[serde(rename = "quorumIndex")]
+    /// Added with quorum rotation in Core 18
+    #[serde(rename = "quorumIndex", default)]
     pub quorum_index: u32,
     #[serde(rename = "minedBlock")]
     pub mined_block: String,
@@ -0,0 +1,241 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use zeromq::{Socket, SocketRecv, SubSocket};
+
+/// Reconnect when nothing has arrived on the socket for this long
+pub const ZMQ_QUIET: Duration = Duration::from_secs(180);
+
+/// Topics published by Dash Core (`-zmqpub<topic>=<address>`)
+const TOPICS: [&str; 6] = [
+    "hashblock",
+    "hashchainlock",
+    "rawtx",
+    "rawtxlock",
+    "hashgovernanceobject",
+    "hashgovernancevote",
+];
+
+#[derive(Debug, Clone)]
+pub enum ZmqNotification {
+    HashBlock(String),
+    HashChainLock(String),
+    RawTx(String),
+    RawTxLock(String),
+    GovernanceObject(String),
+    GovernanceVote(String),
+}
+
+/// Subscribes to a Dash Core ZMQ publisher and forwards notifications to the live updater
+pub struct ZmqListener {
+    url: String,
+    tx: mpsc::Sender<ZmqNotification>,
+}
+
+impl ZmqListener {
+    pub fn new(url: String, tx: mpsc::Sender<ZmqNotification>) -> Self {
+        Self { url, tx }
+    }
+
+    pub async fn run(self) {
+        tracing::info!("ZMQ listener connecting to {}", self.url);
+
+        loop {
+            match self.subscribe().await {
+                Ok(socket) => {
+                    tracing::info!("ZMQ subscribed to {}", self.url);
+                    if self.receive(socket).await.is_err() {
+                        // Receiver side is gone, nothing left to feed
+                        return;
+                    }
+                }
+                Err(e) => tracing::warn!("ZMQ connect to {} failed: {}", self.url, e),
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    }
+
+    async fn subscribe(&self) -> Result<SubSocket, zeromq::ZmqError> {
+        let mut socket = SubSocket::new();
+        socket.connect(&self.url).await?;
+        for topic in TOPICS {
+            socket.subscribe(topic).await?;
+        }
+        Ok(socket)
+    }
+
+    /// Read messages until the socket errors or goes quiet. Errors only when the
+    /// notification channel is closed.
+    async fn receive(&self, mut socket: SubSocket) -> Result<(), ()> {
+        // Last sequence number seen per topic, to notice dropped messages
+        let mut sequences: HashMap<Vec<u8>, u32> = HashMap::new();
+        loop {
+            let msg = match tokio::time::timeout(ZMQ_QUIET, socket.recv()).await {
+                Ok(Ok(msg)) => msg,
+                Ok(Err(e)) => {
+                    tracing::warn!("ZMQ receive failed: {}", e);
+                    return Ok(());
+                }
+                Err(_) => {
+                    tracing::warn!("ZMQ socket quiet for {:?}, reconnecting", ZMQ_QUIET);
+                    return Ok(());
+                }
+            };
+
+            let (Some(topic), Some(body)) = (msg.get(0), msg.get(1)) else {
+                continue;
+            };
+            if let Some(sequence) = msg.get(2).and_then(|frame| parse_sequence(frame)) {
+                if let Some(previous) = sequences.insert(topic.to_vec(), sequence) {
+                    let missed = missed_between(previous, sequence);
+                    if missed > 0 {
+                        // The pollers pick up whatever the dropped messages announced
+                        tracing::warn!(
+                            "ZMQ dropped {} {} notifications",
+                            missed,
+                            String::from_utf8_lossy(topic)
+                        );
+                    }
+                }
+            }
+            let Some(notification) = parse_notification(topic, body) else {
+                continue;
+            };
+            if self.tx.send(notification).await.is_err() {
+                return Err(());
+            }
+        }
+    }
+}
+
+fn parse_notification(topic: &[u8], body: &[u8]) -> Option<ZmqNotification> {
+    let notification = match topic {
+        b"hashblock" => ZmqNotification::HashBlock(to_hex(body)),
+        b"hashchainlock" => ZmqNotification::HashChainLock(to_hex(body)),
+        b"rawtx" => ZmqNotification::RawTx(txid_of(body)),
+        b"rawtxlock" => ZmqNotification::RawTxLock(txid_of(body)),
+        b"hashgovernanceobject" => ZmqNotification::GovernanceObject(to_hex(body)),
+        b"hashgovernancevote" => ZmqNotification::GovernanceVote(to_hex(body)),
+        _ => return None,
+    };
+    Some(notification)
+}
+
+/// Third frame of every notification: the topic's message counter, little-endian
+fn parse_sequence(frame: &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(frame.try_into().ok()?))
+}
+
+/// Messages skipped between two consecutive sequence numbers of a topic
+fn missed_between(previous: u32, sequence: u32) -> u32 {
+    sequence.wrapping_sub(previous).wrapping_sub(1)
+}
+
+/// Txid of a serialized transaction: double SHA-256, displayed byte-reversed
+fn txid_of(raw: &[u8]) -> String {
+    let hash = Sha256::digest(Sha256::digest(raw));
+    let reversed: Vec<u8> = hash.iter().rev().copied().collect();
+    to_hex(&reversed)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+    use zeromq::{PubSocket, SocketSend, ZmqMessage};
+
+    /// Bitcoin's genesis coinbase; txids are computed the same way on Dash
+    const GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+    const GENESIS_TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn txid_is_double_sha256_byte_reversed() {
+        assert_eq!(txid_of(&from_hex(GENESIS_COINBASE)), GENESIS_TXID);
+    }
+
+    #[test]
+    fn parses_topics() {
+        let hash = from_hex(GENESIS_TXID);
+        let raw = from_hex(GENESIS_COINBASE);
+
+        // Hash topics are published in display order already
+        assert!(matches!(
+            parse_notification(b"hashblock", &hash),
+            Some(ZmqNotification::HashBlock(h)) if h == GENESIS_TXID
+        ));
+        assert!(matches!(
+            parse_notification(b"hashchainlock", &hash),
+            Some(ZmqNotification::HashChainLock(h)) if h == GENESIS_TXID
+        ));
+        assert!(matches!(
+            parse_notification(b"hashgovernancevote", &hash),
+            Some(ZmqNotification::GovernanceVote(h)) if h == GENESIS_TXID
+        ));
+        assert!(matches!(
+            parse_notification(b"rawtx", &raw),
+            Some(ZmqNotification::RawTx(txid)) if txid == GENESIS_TXID
+        ));
+        assert!(matches!(
+            parse_notification(b"rawtxlock", &raw),
+            Some(ZmqNotification::RawTxLock(txid)) if txid == GENESIS_TXID
+        ));
+        assert!(parse_notification(b"rawblock", &raw).is_none());
+    }
+
+    #[test]
+    fn parses_sequence_numbers() {
+        assert_eq!(parse_sequence(&[1, 0, 0, 0]), Some(1));
+        assert_eq!(parse_sequence(&[0x78, 0x56, 0x34, 0x12]), Some(0x1234_5678));
+        assert_eq!(parse_sequence(&[1, 0, 0]), None);
+
+        assert_eq!(missed_between(7, 8), 0);
+        assert_eq!(missed_between(7, 10), 2);
+        assert_eq!(missed_between(u32::MAX, 0), 0);
+    }
+
+    #[tokio::test]
+    async fn receives_from_publisher() {
+        let mut publisher = PubSocket::new();
+        let endpoint = publisher.bind("tcp://127.0.0.1:0").await.unwrap();
+
+        let (tx, mut rx) = mpsc::channel(16);
+        tokio::spawn(ZmqListener::new(endpoint.to_string(), tx).run());
+
+        // Subscriptions propagate asynchronously, so publish until one arrives
+        let received = tokio::time::timeout(Duration::from_secs(10), async {
+            let mut sequence = 0u32;
+            loop {
+                let mut msg = ZmqMessage::from("rawtx");
+                msg.push_back(Bytes::from(from_hex(GENESIS_COINBASE)));
+                msg.push_back(Bytes::copy_from_slice(&sequence.to_le_bytes()));
+                publisher.send(msg).await.unwrap();
+                sequence += 1;
+
+                let wait = tokio::time::sleep(Duration::from_millis(50));
+                tokio::select! {
+                    notification = rx.recv() => break notification,
+                    _ = wait => {}
+                }
+            }
+        })
+        .await
+        .expect("no notification from the publisher");
+
+        assert!(matches!(
+            received,
+            Some(ZmqNotification::RawTx(txid)) if txid == GENESIS_TXID
+        ));
+    }
+}
@@ -1,6 +1,11 @@
+// A page served at /{network}/ talks to that network's API; the root uses the default network
+const segment = location.pathname.split('/')[1] || '';
+export const network = segment && !segment.includes('.') ? segment : null;
+export const apiBase = network ? `/${network}` : '';
+
 export const api = {
     async get(path) {
-        const resp = await fetch(path);
+        const resp = await fetch(apiBase + path);
         if (!resp.ok) {
             const err = await resp.json().catch(() => ({ error: resp.statusText }));
             throw new Error(err.error || 'API request failed');
@@ -12,6 +12,7 @@ import { renderMasternode } from './pages/masternode.js';
 import { renderMempool } from './pages/mempool.js';
 import { renderGovernance } from './pages/governance.js';
 import { renderNetwork } from './pages/network.js';
+import { renderPlatform } from './pages/platform.js';
 
 const router = new Router();
 
@@ -25,6 +26,7 @@ router.on('/masternode/:hash', renderMasternode);
 router.on('/mempool', renderMempool);
 router.on('/governance', renderGovernance);
 router.on('/network', renderNetwork);
+router.on('/platform', renderPlatform);
 
 // Mobile nav toggle
 const navToggle = document.getElementById('nav-toggle');
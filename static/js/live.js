@@ -1,3 +1,5 @@
+import { apiBase } from './api.js';
+
 let ws = null;
 let reconnectTimer = null;
 const listeners = new Set();
@@ -13,7 +15,7 @@ export function initLive() {
 
 function connect() {
     const protocol = location.protocol === 'https:' ? 'wss:' : 'ws:';
-    ws = new WebSocket(`${protocol}//${location.host}/api/ws`);
+    ws = new WebSocket(`${protocol}//${location.host}${apiBase}/api/ws`);
 
     ws.onopen = () => {
         const dot = document.getElementById('live-indicator');
@@ -1,12 +1,14 @@
 import { api } from '../api.js';
 import { chainlockBadge, txTypeBadge, instantSendBadge, formatDashValue, formatDashShort, formatTime, formatNumber, hashLink, showLoading, escapeHtml } from '../components.js';
+import { onLiveEvent } from '../live.js';
 
 export async function renderBlockDetail({ id }) {
     const app = document.getElementById('app');
     showLoading(app);
 
+    let block;
     try {
-        const block = await api.get(`/api/block/${id}`);
+        block = await api.get(`/api/block/${id}`);
 
         app.innerHTML = `
             <div class="breadcrumb"><a href="#/blocks">Blocks</a> / ${block.height}</div>
@@ -16,7 +18,7 @@ export async function renderBlockDetail({ id }) {
             </div>
             <div class="page-title">
                 <h1>Block ${formatNumber(block.height)}</h1>
-                ${chainlockBadge(block.chainlock)}
+                <span id="block-chainlock">${chainlockBadge(block.chainlock)}</span>
             </div>
 
             <div class="card" style="margin-bottom:1.5rem">
@@ -71,7 +73,21 @@ export async function renderBlockDetail({ id }) {
         `;
     } catch (e) {
         app.innerHTML = `<div class="error-message"><h2>Error</h2><p>${escapeHtml(e.message)}</p></div>`;
+        return;
     }
+
+    if (block.chainlock) return;
+
+    // A chainlock at or above this height locks the block too
+    const unsub = onLiveEvent(event => {
+        if (event.type === 'ChainLock' && event.data.height >= block.height) {
+            const badge = document.getElementById('block-chainlock');
+            if (badge) badge.innerHTML = chainlockBadge(true);
+            unsub();
+        }
+    });
+
+    return unsub;
 }
 
 function renderCbTx(cb) {
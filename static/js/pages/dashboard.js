@@ -47,7 +47,7 @@ export async function renderDashboard() {
     await loadData();
 
     const unsub = onLiveEvent(event => {
-        if (event.type === 'NewBlock') loadData();
+        if (event.type === 'NewBlock' || event.type === 'Reorg') loadData();
     });
 
     return unsub;
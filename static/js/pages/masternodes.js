@@ -1,14 +1,38 @@
-import { api } from '../api.js';
+import { api, apiBase } from '../api.js';
 import { mnStatusBadge, mnTypeBadge, formatNumber, hashLink, blockLink, addressLink, showLoading, renderPagination, bindPagination, escapeHtml } from '../components.js';
 
 let currentType = 'all';
 let currentStatus = 'all';
 let currentPage = 1;
+let currentSearch = '';
+let currentSort = null;
+let currentOrder = 'asc';
+
+const COLUMNS = [
+    ['pro_tx_hash', 'ProTx Hash'],
+    ['mn_type', 'Type'],
+    ['status', 'Status'],
+    ['service', 'Service'],
+    ['pose_penalty', 'PoSe'],
+    ['last_paid_block', 'Last Paid'],
+    ['registered_height', 'Registered'],
+    ['platform_node_id', 'Platform ID'],
+];
+
+function listQuery() {
+    let query = `type=${currentType}&status=${currentStatus}`;
+    if (currentSearch) query += `&search=${encodeURIComponent(currentSearch)}`;
+    if (currentSort) query += `&sort=${currentSort}&order=${currentOrder}`;
+    return query;
+}
 
 export async function renderMasternodes() {
     currentPage = 1;
     currentType = 'all';
     currentStatus = 'all';
+    currentSearch = '';
+    currentSort = null;
+    currentOrder = 'asc';
 
     const app = document.getElementById('app');
     app.innerHTML = `
@@ -21,6 +45,8 @@ export async function renderMasternodes() {
             <button class="filter-btn active" data-status="all">All Status</button>
             <button class="filter-btn" data-status="enabled">Enabled</button>
             <button class="filter-btn" data-status="pose_banned">Banned</button>
+            <input type="text" class="filter-search" id="mn-search" placeholder="IP, address or ProTx hash..." />
+            <a class="filter-btn" id="mn-export" href="#">CSV</a>
         </div>
         <div class="card">
             <div class="card-body" id="mn-content"><div class="spinner"></div></div>
@@ -47,13 +73,42 @@ export async function renderMasternodes() {
         loadMasternodes();
     });
 
+    let searchTimer = null;
+    document.getElementById('mn-search').addEventListener('input', (e) => {
+        clearTimeout(searchTimer);
+        searchTimer = setTimeout(() => {
+            currentSearch = e.target.value.trim();
+            currentPage = 1;
+            loadMasternodes();
+        }, 300);
+    });
+
+    document.getElementById('mn-export').addEventListener('click', (e) => {
+        e.preventDefault();
+        window.location.href = `${apiBase}/api/masternodes?${listQuery()}&format=csv`;
+    });
+
+    // Sort by a column; clicking it again flips the order
+    document.getElementById('mn-content').addEventListener('click', (e) => {
+        const th = e.target.closest('th[data-sort]');
+        if (!th) return;
+        if (currentSort === th.dataset.sort) {
+            currentOrder = currentOrder === 'asc' ? 'desc' : 'asc';
+        } else {
+            currentSort = th.dataset.sort;
+            currentOrder = 'asc';
+        }
+        currentPage = 1;
+        loadMasternodes();
+    });
+
     await loadMasternodes();
 }
 
 async function loadMasternodes() {
     showLoading('mn-content');
     try {
-        const data = await api.get(`/api/masternodes?type=${currentType}&status=${currentStatus}&page=${currentPage}&limit=50`);
+        const data = await api.get(`/api/masternodes?${listQuery()}&page=${currentPage}&limit=50`);
 
         const rows = data.masternodes.map(mn => `
             <tr>
@@ -63,13 +118,19 @@ async function loadMasternodes() {
                 <td class="mono" style="font-size:0.85rem">${mn.service}</td>
                 <td>${mn.pose_penalty > 0 ? `<span class="text-warning">${mn.pose_penalty}</span>` : '<span class="text-muted">0</span>'}</td>
                 <td>${mn.last_paid_block > 0 ? blockLink(mn.last_paid_block) : '<span class="text-muted">Never</span>'}</td>
+                <td>${mn.registered_height != null ? blockLink(mn.registered_height) : '<span class="text-muted">-</span>'}</td>
                 ${mn.platform_node_id ? `<td class="mono" style="font-size:0.85rem">${mn.platform_node_id}</td>` : '<td class="text-muted">-</td>'}
             </tr>
         `).join('');
 
+        const headers = COLUMNS.map(([field, label]) => {
+            const arrow = currentSort === field ? (currentOrder === 'asc' ? ' &#9650;' : ' &#9660;') : '';
+            return `<th class="sortable${currentSort === field ? ' sorted' : ''}" data-sort="${field}">${label}${arrow}</th>`;
+        }).join('');
+
         document.getElementById('mn-content').innerHTML = `
             <table class="data-table">
-                <thead><tr><th>ProTx Hash</th><th>Type</th><th>Status</th><th>Service</th><th>PoSe</th><th>Last Paid</th><th>Platform ID</th></tr></thead>
+                <thead><tr>${headers}</tr></thead>
                 <tbody>${rows}</tbody>
             </table>
         `;
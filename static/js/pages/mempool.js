@@ -35,12 +35,18 @@ export async function renderMempool() {
                         <div class="stat-label">Total Fees</div>
                     </div>
                 </div>
+                <div class="stat-card">
+                    <div class="stat-content">
+                        <div class="stat-value">${mempool.locked} / ${mempool.locked + mempool.unlocked}</div>
+                        <div class="stat-label">InstantSend Locked</div>
+                    </div>
+                </div>
             </div>
 
             <div class="card">
                 <div class="card-header"><h3>Pending Transactions</h3></div>
                 <div class="card-body" id="mempool-txs">
-                    ${renderTxList(mempool.transactions)}
+                    ${renderTxList(mempool.entries)}
                 </div>
             </div>
         `;
@@ -51,26 +57,61 @@ export async function renderMempool() {
     const unsub = onLiveEvent(event => {
         if (event.type === 'MempoolUpdate') {
             renderMempool();
+        } else if (event.type === 'NewTransaction') {
+            prependTx(event.data);
+        } else if (event.type === 'InstantLock') {
+            markLocked(event.data.txid);
         }
     });
 
     return unsub;
 }
 
-function renderTxList(txids) {
-    if (!txids || txids.length === 0) {
-        return '<p class="text-muted" style="padding:1rem">No pending transactions</p>';
+function prependTx(tx) {
+    const container = document.getElementById('mempool-txs');
+    if (!container || container.querySelector(`tr[data-txid="${tx.txid}"]`)) return;
+
+    let tbody = container.querySelector('tbody');
+    if (!tbody) {
+        container.innerHTML = renderTxList([]);
+        tbody = container.querySelector('tbody');
     }
 
-    const rows = txids.map(txid => `
-        <tr>
-            <td>${hashLink(txid)}</td>
+    const row = document.createElement('tr');
+    row.dataset.txid = tx.txid;
+    row.innerHTML = `
+        <td>${hashLink(tx.txid)}</td>
+        <td>${txTypeBadge(tx.tx_type)}</td>
+        <td class="mono">${formatDashValue(tx.total_output)} DASH</td>
+        <td></td>
+        <td></td>
+        <td>just now</td>
+        <td class="is-cell">${instantSendBadge(tx.instantlock, false)}</td>
+    `;
+    tbody.prepend(row);
+}
+
+function markLocked(txid) {
+    const cell = document.querySelector(`#mempool-txs tr[data-txid="${txid}"] .is-cell`);
+    if (cell) cell.innerHTML = instantSendBadge(true, false);
+}
+
+function renderTxList(entries) {
+    const rows = (entries || []).map(e => `
+        <tr data-txid="${e.txid}">
+            <td>${hashLink(e.txid)}</td>
+            <td>${txTypeBadge(e.tx_type)}</td>
+            <td class="mono">${formatDashValue(e.total_output)} DASH</td>
+            <td class="mono">${e.fee_rate.toFixed(2)}</td>
+            <td>${formatNumber(e.size)}</td>
+            <td>${timeAgo(e.time)}</td>
+            <td class="is-cell">${instantSendBadge(e.instantlock, false)}</td>
         </tr>
     `).join('');
 
     return `
         <table class="data-table">
-            <thead><tr><th>TxID</th></tr></thead>
+            <thead><tr><th>TxID</th><th>Type</th><th>Output</th><th>Fee (duffs/B)</th><th>Size</th><th>Age</th><th>IS</th></tr></thead>
             <tbody>${rows}</tbody>
         </table>
     `;
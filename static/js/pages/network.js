@@ -98,8 +98,64 @@ export async function renderNetwork() {
                     </div>
                 </div>
             </div>
+
+            <div id="mn-stats" style="margin-top:1.5rem"></div>
         `;
     } catch (e) {
         app.innerHTML = `<div class="error-message"><h2>Error</h2><p>${escapeHtml(e.message)}</p></div>`;
+        return;
+    }
+
+    loadMasternodeStats();
+}
+
+function countTable(title, headers, rows) {
+    return `
+        <div class="card">
+            <div class="card-header"><h3>${title}</h3></div>
+            <div class="card-body">
+                <table class="data-table">
+                    <thead><tr>${headers.map(h => `<th>${h}</th>`).join('')}</tr></thead>
+                    <tbody>${rows.join('') || `<tr><td colspan="${headers.length}" class="text-muted">No data</td></tr>`}</tbody>
+                </table>
+            </div>
+        </div>
+    `;
+}
+
+// Loaded after the page so a slow or failing breakdown doesn't hold up the rest
+async function loadMasternodeStats() {
+    const container = document.getElementById('mn-stats');
+    if (!container) return;
+    try {
+        const stats = await api.get('/api/masternodes/stats');
+        const top = (items) => items.slice(0, 10);
+
+        const versions = top(stats.versions).map(v => `
+            <tr><td class="mono">${escapeHtml(v.user_agent)}</td><td>${v.protocol_version}</td><td>${formatNumber(v.count)}</td></tr>
+        `);
+        const countries = top(stats.countries || []).map(c => `
+            <tr><td>${escapeHtml(c.name)}</td><td>${formatNumber(c.count)}</td></tr>
+        `);
+        const asns = top(stats.asns || []).map(a => `
+            <tr><td>${a.asn != null ? `AS${a.asn}` : '-'}</td><td>${escapeHtml(a.name || 'Unknown')}</td><td>${formatNumber(a.count)}</td></tr>
+        `);
+        const pose = stats.pose_histogram.map(b => `
+            <tr><td>${b.max != null ? `${b.min} - ${b.max - 1}` : `${b.min}+`}</td><td>${formatNumber(b.regular)}</td><td>${formatNumber(b.evo)}</td></tr>
+        `);
+
+        container.innerHTML = `
+            <div class="dashboard-columns">
+                ${countTable(`Versions <span class="text-muted" style="font-size:0.8rem">(${stats.versions_known} of ${stats.total} known)</span>`, ['User Agent', 'Protocol', 'Masternodes'], versions)}
+                ${countTable(`PoSe Penalty <span class="text-muted" style="font-size:0.8rem">(ban above ${stats.pose_max_penalty})</span>`, ['Penalty', 'Regular', 'Evo'], pose)}
+            </div>
+            ${stats.countries ? `
+            <div class="dashboard-columns" style="margin-top:1.5rem">
+                ${countTable(`Countries <span class="text-muted" style="font-size:0.8rem">(${stats.located} of ${stats.total} located)</span>`, ['Country', 'Masternodes'], countries)}
+                ${countTable('Networks', ['AS', 'Name', 'Masternodes'], asns)}
+            </div>` : ''}
+        `;
+    } catch (e) {
+        container.innerHTML = `<div class="error-message">${escapeHtml(e.message)}</div>`;
     }
 }
@@ -0,0 +1,87 @@
+import { api } from '../api.js';
+import { mnStatusBadge, formatNumber, timeAgo, blockLink, showLoading, escapeHtml } from '../components.js';
+
+export async function renderPlatform() {
+    const app = document.getElementById('app');
+    app.innerHTML = `
+        <div class="page-title"><h1>Platform</h1></div>
+        <div id="platform-stats"></div>
+        <div class="filter-bar">
+            <button class="filter-btn" id="platform-probe">Check HTTP ports</button>
+        </div>
+        <div class="card">
+            <div class="card-header"><h3>Evo Nodes</h3></div>
+            <div class="card-body" id="platform-content"><div class="spinner"></div></div>
+        </div>
+    `;
+
+    document.getElementById('platform-probe').addEventListener('click', () => loadNodes(true));
+    await loadNodes(false);
+}
+
+function probeCell(node, probed) {
+    if (!probed) return '<td class="text-muted">-</td>';
+    if (!node.probe) return '<td class="text-muted">Not checked</td>';
+    if (node.probe.reachable) {
+        return `<td class="text-success">Open <span class="text-muted">${node.probe.latency_ms} ms</span></td>`;
+    }
+    return `<td class="text-danger" title="${escapeHtml(node.probe.error || '')}">Closed <span class="text-muted">${timeAgo(node.probe.checked_at)}</span></td>`;
+}
+
+async function loadNodes(probe) {
+    showLoading('platform-content');
+    try {
+        const data = await api.get(`/api/platform/nodes${probe ? '?probe=true' : ''}`);
+
+        document.getElementById('platform-stats').innerHTML = `
+            <div class="stats-grid" style="margin-bottom:1.5rem">
+                <div class="stat-card">
+                    <div class="stat-content">
+                        <div class="stat-value">${formatNumber(data.total)}</div>
+                        <div class="stat-label">Evo Nodes</div>
+                    </div>
+                </div>
+                <div class="stat-card">
+                    <div class="stat-content">
+                        <div class="stat-value">${formatNumber(data.enabled)}</div>
+                        <div class="stat-label">Enabled</div>
+                    </div>
+                </div>
+                <div class="stat-card">
+                    <div class="stat-content">
+                        <div class="stat-value">${formatNumber(data.platform_banned)}</div>
+                        <div class="stat-label">Platform Banned</div>
+                    </div>
+                </div>
+                <div class="stat-card">
+                    <div class="stat-content">
+                        <div class="stat-value">${probe ? `${data.reachable} / ${data.probed}` : '-'}</div>
+                        <div class="stat-label">HTTP Reachable</div>
+                    </div>
+                </div>
+            </div>
+        `;
+
+        const rows = data.nodes.map(node => `
+            <tr>
+                <td><a href="#/masternode/${node.pro_tx_hash}" class="hash-link mono">${node.pro_tx_hash.slice(0, 16)}...</a></td>
+                <td>${mnStatusBadge(node.status)}</td>
+                <td class="mono" style="font-size:0.85rem">${node.platform_node_id ? escapeHtml(node.platform_node_id) : '-'}</td>
+                <td class="mono" style="font-size:0.85rem">${node.platform_http_address ? escapeHtml(node.platform_http_address) : '-'}</td>
+                <td>${node.platform_p2p_port ?? '-'}</td>
+                <td>${node.is_platform_banned ? '<span class="text-danger">Banned</span>' : node.is_platform_banned === false ? 'No' : '<span class="text-muted">-</span>'}</td>
+                <td>${node.platform_ban_height_updated ? blockLink(node.platform_ban_height_updated) : '<span class="text-muted">-</span>'}</td>
+                ${probeCell(node, probe)}
+            </tr>
+        `).join('');
+
+        document.getElementById('platform-content').innerHTML = `
+            <table class="data-table">
+                <thead><tr><th>ProTx Hash</th><th>Status</th><th>Platform ID</th><th>HTTP</th><th>P2P Port</th><th>Platform Ban</th><th>Ban Updated</th><th>Probe</th></tr></thead>
+                <tbody>${rows}</tbody>
+            </table>
+        `;
+    } catch (e) {
+        document.getElementById('platform-content').innerHTML = `<div class="error-message">${escapeHtml(e.message)}</div>`;
+    }
+}